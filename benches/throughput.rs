@@ -0,0 +1,279 @@
+//! Throughput benchmarks, reported in MB/s.
+//!
+//! `cargo bench --bench throughput` runs every benchmark and compares it to the baseline
+//! committed in `benches/throughput-baseline.txt` (or the `--baseline` file), failing when one is
+//! slower than the baseline by more than the threshold (10% by default).
+//!
+//! ```sh
+//! cargo bench --bench throughput -- --save-baseline       # record the current throughput
+//! cargo bench --bench throughput -- --threshold 5         # fail on a 5% regression
+//! cargo bench --bench throughput -- --baseline ci.txt     # baseline of another machine
+//! cargo bench --bench throughput -- nested                # only benches whose name contains `nested`
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+use react_props_replacer::{parse, transform, Action, Component, Span};
+
+/// Minimum measuring time of a benchmark, after one warm up run.
+const MEASURE_TIME: Duration = Duration::from_secs(2);
+const DEFAULT_THRESHOLD: f64 = 10.0;
+const LARGE_FILE_SIZE: usize = 5 * 1024 * 1024;
+/// Baseline file, relative to the crate folder.
+const BASELINE_FILE: &str = "benches/throughput-baseline.txt";
+
+struct Bench {
+    name: &'static str,
+    /// Bytes processed by one run.
+    bytes: usize,
+    run: Box<dyn Fn()>,
+}
+
+fn main() {
+    let mut save_baseline = false;
+    let mut threshold = DEFAULT_THRESHOLD;
+    let mut baseline_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(BASELINE_FILE);
+    let mut filter = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Passed by `cargo bench` to every bench target
+            "--bench" => (),
+            "--save-baseline" => save_baseline = true,
+            "--threshold" => {
+                threshold = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| exit_with("--threshold requires a percentage"));
+            }
+            "--baseline" => {
+                baseline_path = args
+                    .next()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| exit_with("--baseline requires a file"));
+            }
+            _ if arg.starts_with('-') => exit_with(&format!("Unknown option {}", arg)),
+            _ => filter = Some(arg),
+        }
+    }
+
+    let mut baseline = read_baseline(&baseline_path);
+    let mut regressions = vec![];
+
+    println!(
+        "{:<28} {:>10} {:>10} {:>10} {:>8}",
+        "BENCH", "SIZE", "MB/S", "BASELINE", "DELTA"
+    );
+    for bench in benches() {
+        if filter
+            .as_ref()
+            .is_some_and(|filter| !bench.name.contains(filter))
+        {
+            continue;
+        }
+
+        let throughput = measure(&bench);
+        let (previous, delta) = match baseline.get(bench.name) {
+            Some(&previous) => {
+                let delta = (throughput - previous) / previous * 100.0;
+                if delta < -threshold {
+                    regressions.push(bench.name);
+                }
+                (format!("{:.1}", previous), format!("{:+.1}%", delta))
+            }
+            None => ("-".to_string(), "-".to_string()),
+        };
+
+        println!(
+            "{:<28} {:>10} {:>10.1} {:>10} {:>8}",
+            bench.name,
+            human_size(bench.bytes),
+            throughput,
+            previous,
+            delta
+        );
+        baseline.insert(bench.name.to_string(), throughput);
+    }
+
+    if save_baseline {
+        write_baseline(&baseline_path, &baseline);
+        println!("\nBaseline saved to {}", baseline_path.display());
+    } else if !regressions.is_empty() {
+        exit_with(&format!(
+            "\nThroughput regressed by more than {}%: {}",
+            threshold,
+            regressions.join(", ")
+        ));
+    }
+}
+
+fn benches() -> Vec<Bench> {
+    let actions = vec![
+        Action::RemoveProp("data-testid".to_string()),
+        Action::RenameProp("class".to_string(), "className".to_string()),
+    ];
+
+    let small = small_components();
+    let large = large_file();
+    let nested = nested_tree();
+
+    vec![
+        component_from("component_from/small", small.clone()),
+        component_from("component_from/large_file", large.clone()),
+        component_from("component_from/nested", nested.clone()),
+        transform_files("transform/small", vec![small; 200], actions.clone()),
+        transform_files("transform/large_file", vec![large], actions.clone()),
+        transform_files("transform/nested", vec![nested], actions),
+    ]
+}
+
+/// Parse every opening tag of the content with `Component::from`.
+fn component_from(name: &'static str, content: String) -> Bench {
+    let spans: Vec<Span> = parse(&content)
+        .components()
+        .iter()
+        .map(|component| component.span)
+        .collect();
+    let bytes = spans.iter().map(Span::len).sum();
+
+    Bench {
+        name,
+        bytes,
+        run: Box::new(move || {
+            for span in &spans {
+                black_box(Component::from(black_box(&content[span.start..])));
+            }
+        }),
+    }
+}
+
+/// Transform each file, as a run over a folder does.
+fn transform_files(name: &'static str, files: Vec<String>, actions: Vec<Action>) -> Bench {
+    Bench {
+        name,
+        bytes: files.iter().map(String::len).sum(),
+        run: Box::new(move || {
+            for content in &files {
+                black_box(transform(black_box(content), &actions).unwrap());
+            }
+        }),
+    }
+}
+
+/// Throughput in MB/s, from the best of the runs done in `MEASURE_TIME`.
+fn measure(bench: &Bench) -> f64 {
+    (bench.run)();
+
+    let mut best = Duration::MAX;
+    let started = Instant::now();
+    while started.elapsed() < MEASURE_TIME {
+        let run_started = Instant::now();
+        (bench.run)();
+        best = best.min(run_started.elapsed());
+    }
+
+    bench.bytes as f64 / (1024.0 * 1024.0) / best.as_secs_f64()
+}
+
+/// A component file like the ones of a React app, about 2 KB.
+fn small_components() -> String {
+    let mut content = String::from("import React from 'react';\n\n");
+    for i in 0..8 {
+        content.push_str(&format!(
+            r#"export const Card{0} = ({{ title, items, ...rest }}) => (
+  <section class="card" data-testid="card-{0}" {{...rest}}>
+    <h2 className={{styles.title}}>{{title}}</h2>
+    <img src={{`/img/${{title}}.png`}} alt="" />
+    <Button variant="primary" disabled onClick={{() => rest.onOpen({0})}}>Open</Button>
+  </section>
+);
+
+"#,
+            i
+        ));
+    }
+
+    content
+}
+
+/// A single generated file of `LARGE_FILE_SIZE`, made of forms.
+fn large_file() -> String {
+    let mut content = String::with_capacity(LARGE_FILE_SIZE + 1024);
+    let mut i = 0;
+    while content.len() < LARGE_FILE_SIZE {
+        content.push_str(&format!(
+            r#"<form id="form-{0}" data-testid="form-{0}" onSubmit={{handleSubmit}}>
+  <label for="name-{0}" class="label">Name</label>
+  <input id="name-{0}" type="text" value={{values.name}} onChange={{e => set({{ name: e.target.value }})}} />
+  <select class="select" {{...register('kind')}}><option value="a">A</option></select>
+  <button type="submit" data-testid="submit-{0}" disabled={{!valid}}>Send</button>
+</form>
+"#,
+            i
+        ));
+        i += 1;
+    }
+
+    content
+}
+
+/// Trees nested 500 levels deep, about 1 MB.
+fn nested_tree() -> String {
+    const DEPTH: usize = 500;
+
+    let mut content = String::new();
+    while content.len() < 1024 * 1024 {
+        for depth in 0..DEPTH {
+            content.push_str(&format!(
+                "<div class=\"level-{0}\" data-testid=\"node\" style={{{{ margin: {0} }}}}>\n",
+                depth
+            ));
+        }
+        content.push_str("<span>leaf</span>\n");
+        content.push_str(&"</div>\n".repeat(DEPTH));
+    }
+
+    content
+}
+
+/// Baseline lines are `<bench name> <MB/s>`.
+fn read_baseline(path: &PathBuf) -> BTreeMap<String, f64> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (name, throughput) = line.rsplit_once(' ')?;
+            Some((name.to_string(), throughput.parse().ok()?))
+        })
+        .collect()
+}
+
+fn write_baseline(path: &PathBuf, baseline: &BTreeMap<String, f64>) {
+    let content: String = baseline
+        .iter()
+        .map(|(name, throughput)| format!("{} {:.3}\n", name, throughput))
+        .collect();
+
+    if let Err(e) = fs::write(path, content) {
+        exit_with(&format!("Unable to save the baseline: {}", e));
+    }
+}
+
+fn human_size(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1048575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
@@ -0,0 +1,28 @@
+use std::fs;
+
+use react_props_replacer::{transform, Action, Component, Edits, ElementTransform};
+
+/// Move the legacy `data-track` analytics prop to `data-analytics-id`, keeping its value.
+struct LegacyAnalytics;
+
+impl ElementTransform for LegacyAnalytics {
+    fn name(&self) -> &str {
+        "legacy-analytics"
+    }
+
+    fn visit(&self, component: &Component, edits: &mut Edits) -> Result<(), String> {
+        if let Some(prop) = component.prop("data-track") {
+            edits.rename_prop(prop, "data-analytics-id");
+        }
+        Ok(())
+    }
+}
+
+fn main() {
+    let content = fs::read_to_string("./examples/assets/sample_1.jsx").expect("Read error");
+
+    match transform(&content, &[Action::custom(LegacyAnalytics)]) {
+        Ok(content) => print!("{}", content),
+        Err(e) => eprintln!("Application error: {}", e),
+    }
+}
@@ -18,7 +18,7 @@ fn main() -> Result<(), Error> {
 
     if let Err(e) = react_props_replacer::run(&config) {
         eprintln!("Application error: {}", e);
-        process::exit(1);
+        process::exit(e.exit_code());
     }
 
     Ok(())
@@ -0,0 +1,124 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::Error;
+
+/// Cache file name, created in the input folder (or in the folder of the input file).
+pub const CACHE_FILE_NAME: &str = ".propsreplacer-cache";
+
+/// First line of the cache file, changed when the format or the hashes change.
+const HEADER: &str = "propsreplacer-cache 1";
+
+/// Result of the last run on a file.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Entry {
+    /// Hash of the file bytes.
+    pub content: u64,
+    /// Hash of the actions applied to the file, of its output path, of the run mode and of the
+    /// checks of the output (`verify`, `idempotent`).
+    pub actions: u64,
+    /// Whether the actions changed the file.
+    pub changed: bool,
+}
+
+/// Incremental cache of the files processed, stored as one line per file:
+/// `<content hash> <actions hash> <changed> <path>`.
+#[derive(Debug, Default)]
+pub struct Cache {
+    entries: BTreeMap<PathBuf, Entry>,
+}
+
+impl Cache {
+    /// Load a cache file. A missing or invalid cache is empty.
+    pub fn load(path: &Path) -> Self {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let mut lines = content.lines();
+
+        if lines.next() != Some(HEADER) {
+            return Self::default();
+        }
+
+        let entries = lines
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, ' ');
+                let entry = Entry {
+                    content: u64::from_str_radix(fields.next()?, 16).ok()?,
+                    actions: u64::from_str_radix(fields.next()?, 16).ok()?,
+                    changed: fields.next()? == "1",
+                };
+                Some((PathBuf::from(fields.next()?), entry))
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut content = format!("{}\n", HEADER);
+
+        for (file, entry) in &self.entries {
+            content.push_str(&format!(
+                "{:016x} {:016x} {} {}\n",
+                entry.content,
+                entry.actions,
+                u8::from(entry.changed),
+                file.display()
+            ));
+        }
+
+        fs::write(path, content).map_err(|err| Error::io(path, err))
+    }
+
+    pub fn get(&self, file: &Path) -> Option<&Entry> {
+        self.entries.get(file)
+    }
+
+    pub fn insert(&mut self, file: &Path, entry: Entry) {
+        self.entries.insert(file.to_path_buf(), entry);
+    }
+
+    pub fn remove(&mut self, file: &Path) {
+        self.entries.remove(file);
+    }
+}
+
+/// FNV-1a hash, stable across builds and platforms unlike the std hashers.
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_entries() {
+        let path = std::env::temp_dir().join("propsreplacer-cache-test");
+        let entry = Entry {
+            content: hash(b"<div id='a' />"),
+            actions: hash(b"[RemoveProp(\"id\")]"),
+            changed: true,
+        };
+
+        let mut cache = Cache::default();
+        cache.insert(Path::new("src/my file.jsx"), entry);
+        cache.save(&path).unwrap();
+
+        let cache = Cache::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(cache.get(Path::new("src/my file.jsx")), Some(&entry));
+        assert!(Cache::load(Path::new("missing-cache")).entries.is_empty());
+    }
+
+    #[test]
+    fn stable_hash() {
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,602 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::{Action, Mode, Preset},
+    error::Error,
+    inventory::ReportFormat,
+    summary::SummaryFormat,
+};
+
+const DEFAULT_REMOVE_PROP: &str = "data-testid";
+
+/// Subcommands accepted as first argument. Without subcommand, every action option is allowed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Command {
+    Remove,
+    Rename,
+    Check,
+    Report,
+    Undo,
+}
+
+impl Command {
+    const ALL: [Command; 5] = [
+        Self::Remove,
+        Self::Rename,
+        Self::Check,
+        Self::Report,
+        Self::Undo,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Remove => "remove",
+            Self::Rename => "rename",
+            Self::Check => "check",
+            Self::Report => "report",
+            Self::Undo => "undo",
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self {
+            Self::Remove => "Remove props from the elements (default)",
+            Self::Rename => "Rename props of the elements",
+            Self::Check => "Report the props that would be changed, without writing any file",
+            Self::Report => "Print the uses of every tag and prop of a file or folder",
+            Self::Undo => "Revert the files rewritten in place by the last run",
+        }
+    }
+
+    fn mode(&self) -> Mode {
+        match self {
+            Self::Remove | Self::Rename => Mode::Transform,
+            Self::Check => Mode::Check,
+            Self::Report => Mode::Report,
+            Self::Undo => Mode::Undo,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum OptKind {
+    Output,
+    RemoveProp,
+    RenameProp,
+    Preset,
+    Config,
+    NoConfig,
+    Env,
+    Format,
+    Summary,
+    Jobs,
+    Cache,
+    ChangedSince,
+    Verify,
+    Idempotent,
+    SourceMap,
+    Watch,
+    Help,
+    Version,
+}
+
+#[derive(Debug, PartialEq)]
+enum OptValue {
+    None,
+    Required(&'static str),
+    Optional(&'static str),
+}
+
+struct Opt {
+    kind: OptKind,
+    long: &'static str,
+    short: Option<char>,
+    value: OptValue,
+    help: &'static str,
+    /// Subcommands accepting the option (empty means every subcommand).
+    commands: &'static [Command],
+}
+
+const OPTIONS: &[Opt] = &[
+    Opt {
+        kind: OptKind::Output,
+        long: "output",
+        short: Some('o'),
+        value: OptValue::Required("file"),
+        help: "Output file, `-` for stdout (default: <input>.updated.<ext>)",
+        commands: &[Command::Remove, Command::Rename],
+    },
+    Opt {
+        kind: OptKind::RemoveProp,
+        long: "remove-prop",
+        short: None,
+        value: OptValue::Optional("name[,name...]"),
+        help: "Remove a prop, repeatable (default: \"data-testid\")",
+        commands: &[Command::Remove, Command::Check],
+    },
+    Opt {
+        kind: OptKind::RenameProp,
+        long: "rename-prop",
+        short: None,
+        value: OptValue::Required("old=new[,old=new...]"),
+        help: "Rename a prop, repeatable",
+        commands: &[Command::Rename, Command::Check],
+    },
+    Opt {
+        kind: OptKind::Preset,
+        long: "preset",
+        short: Some('p'),
+        value: OptValue::Required("name[,name...]"),
+        help: "Apply a built-in preset, repeatable (see below)",
+        commands: &[Command::Remove, Command::Rename, Command::Check],
+    },
+    Opt {
+        kind: OptKind::Config,
+        long: "config",
+        short: Some('c'),
+        value: OptValue::Required("file"),
+        help: "Config file with rules (default: nearest propsreplacer.json)",
+        commands: &[],
+    },
+    Opt {
+        kind: OptKind::NoConfig,
+        long: "no-config",
+        short: None,
+        value: OptValue::None,
+        help: "Do not look for a config file",
+        commands: &[],
+    },
+    Opt {
+        kind: OptKind::Env,
+        long: "env",
+        short: Some('e'),
+        value: OptValue::Required("name"),
+        help: "Environment of the config file rules to apply",
+        commands: &[],
+    },
+    Opt {
+        kind: OptKind::Format,
+        long: "format",
+        short: Some('f'),
+        value: OptValue::Required("table|json|csv"),
+        help: "Format of the report (default: table)",
+        commands: &[Command::Report],
+    },
+    Opt {
+        kind: OptKind::Summary,
+        long: "report",
+        short: None,
+        value: OptValue::Required("text|json|sarif|junit"),
+        help: "Format of the run summary, with every prop changed (default: text)",
+        commands: &[Command::Remove, Command::Rename, Command::Check],
+    },
+    Opt {
+        kind: OptKind::Jobs,
+        long: "jobs",
+        short: Some('j'),
+        value: OptValue::Required("n"),
+        help: "Number of files of a folder processed at the same time (default: CPUs)",
+        commands: &[],
+    },
+    Opt {
+        kind: OptKind::Cache,
+        long: "cache",
+        short: None,
+        value: OptValue::None,
+        help: "Skip the files unchanged since the last run (.propsreplacer-cache)",
+        commands: &[Command::Remove, Command::Rename, Command::Check],
+    },
+    Opt {
+        kind: OptKind::ChangedSince,
+        long: "changed-since",
+        short: None,
+        value: OptValue::Required("ref"),
+        help: "Only process the files of a folder changed since a git ref",
+        commands: &[Command::Remove, Command::Rename, Command::Check],
+    },
+    Opt {
+        kind: OptKind::Verify,
+        long: "verify",
+        short: None,
+        value: OptValue::None,
+        help: "Refuse outputs whose elements or untouched props differ from the input",
+        commands: &[Command::Remove, Command::Rename],
+    },
+    Opt {
+        kind: OptKind::Idempotent,
+        long: "idempotent",
+        short: None,
+        value: OptValue::None,
+        help: "Run the actions again on the outputs and refuse the ones changed again",
+        commands: &[Command::Remove, Command::Rename, Command::Check],
+    },
+    Opt {
+        kind: OptKind::SourceMap,
+        long: "source-map",
+        short: None,
+        value: OptValue::None,
+        help: "Write a source map next to every JS/TS output file (<output>.map)",
+        commands: &[Command::Remove, Command::Rename],
+    },
+    Opt {
+        kind: OptKind::Watch,
+        long: "watch",
+        short: Some('w'),
+        value: OptValue::None,
+        help: "Keep running and process the changed files again",
+        commands: &[],
+    },
+    Opt {
+        kind: OptKind::Help,
+        long: "help",
+        short: Some('h'),
+        value: OptValue::None,
+        help: "Print this help",
+        commands: &[],
+    },
+    Opt {
+        kind: OptKind::Version,
+        long: "version",
+        short: Some('V'),
+        value: OptValue::None,
+        help: "Print the version",
+        commands: &[],
+    },
+];
+
+/// Arguments parsed from the command line.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub command: Option<Command>,
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub actions: Vec<Action>,
+    pub config: Option<PathBuf>,
+    pub no_config: bool,
+    pub env: Option<String>,
+    pub format: Option<ReportFormat>,
+    pub summary: Option<SummaryFormat>,
+    pub jobs: Option<usize>,
+    pub cache: bool,
+    pub changed_since: Option<String>,
+    pub verify: bool,
+    pub idempotent: bool,
+    pub source_map: bool,
+    pub watch: bool,
+    pub help: bool,
+    pub version: bool,
+}
+
+impl Args {
+    pub fn mode(&self) -> Mode {
+        self.command.map_or(Mode::Transform, |c| c.mode())
+    }
+}
+
+/// Parse the command line arguments (without the binary name).
+pub fn parse(args: &[String]) -> Result<Args, Error> {
+    let mut parsed = Args::default();
+    let mut args = args.iter().peekable();
+
+    if let Some(command) = args
+        .peek()
+        .and_then(|arg| Command::ALL.iter().find(|c| c.name() == arg.as_str()))
+    {
+        parsed.command = Some(*command);
+        args.next();
+    }
+
+    let mut only_positional = false;
+
+    while let Some(arg) = args.next() {
+        if only_positional || !arg.starts_with('-') || arg == "-" {
+            if parsed.input.is_some() {
+                return Err(Error::Config(format!("Unexpected argument `{}`.", arg)));
+            }
+            parsed.input = Some(PathBuf::from(arg));
+            continue;
+        }
+
+        if arg == "--" {
+            only_positional = true;
+            continue;
+        }
+
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+
+        let opt = find_option(name)
+            .ok_or_else(|| Error::Config(format!("Unknown option `{}`.", name)))?;
+
+        if let Some(command) = parsed.command {
+            if !opt.commands.is_empty() && !opt.commands.contains(&command) {
+                return Err(Error::Config(format!(
+                    "Option `--{}` can not be used with `{}`.",
+                    opt.long,
+                    command.name()
+                )));
+            }
+        }
+
+        let value = match opt.value {
+            OptValue::None => {
+                if inline_value.is_some() {
+                    return Err(Error::Config(format!(
+                        "Option `--{}` does not take a value.",
+                        opt.long
+                    )));
+                }
+                None
+            }
+            OptValue::Required(_) => match inline_value {
+                Some(value) => Some(value),
+                None => Some(
+                    args.next()
+                        .ok_or_else(|| {
+                            Error::Config(format!("Option `--{}` requires a value.", opt.long))
+                        })?
+                        .to_string(),
+                ),
+            },
+            // A separate value (`--remove-prop name`) is taken when it can not be the input path:
+            // the input is already set, or no such path exists.
+            OptValue::Optional(_) => inline_value.or_else(|| {
+                args.next_if(|next| {
+                    !next.starts_with('-')
+                        && (parsed.input.is_some() || !Path::new(next.as_str()).exists())
+                })
+                .cloned()
+            }),
+        };
+
+        match opt.kind {
+            OptKind::Output => parsed.output = value.map(PathBuf::from),
+            OptKind::RemoveProp => {
+                let value = value.unwrap_or_else(|| DEFAULT_REMOVE_PROP.to_string());
+                for name in split_list(&value) {
+                    parsed.actions.push(Action::RemoveProp(name.to_string()));
+                }
+            }
+            OptKind::RenameProp => {
+                for pair in split_list(&value.unwrap_or_default()) {
+                    match pair.split_once('=') {
+                        Some((old, new)) if !old.is_empty() && !new.is_empty() => parsed
+                            .actions
+                            .push(Action::RenameProp(old.to_string(), new.to_string())),
+                        _ => {
+                            return Err(Error::Config(format!(
+                                "Invalid `--rename-prop` value `{}`, expected `old=new`.",
+                                pair
+                            )))
+                        }
+                    }
+                }
+            }
+            OptKind::Preset => {
+                for name in split_list(&value.unwrap_or_default()) {
+                    parsed.actions.extend(Preset::from_name(name)?.actions());
+                }
+            }
+            OptKind::Cache => parsed.cache = true,
+            OptKind::ChangedSince => parsed.changed_since = value,
+            OptKind::Verify => parsed.verify = true,
+            OptKind::Idempotent => parsed.idempotent = true,
+            OptKind::SourceMap => parsed.source_map = true,
+            OptKind::Watch => parsed.watch = true,
+            OptKind::Config => parsed.config = value.map(PathBuf::from),
+            OptKind::NoConfig => parsed.no_config = true,
+            OptKind::Env => parsed.env = value,
+            OptKind::Format => parsed.format = Some(value.unwrap_or_default().parse()?),
+            OptKind::Summary => parsed.summary = Some(value.unwrap_or_default().parse()?),
+            OptKind::Jobs => {
+                let value = value.unwrap_or_default();
+                let jobs = value.parse().ok().filter(|jobs| *jobs > 0).ok_or_else(|| {
+                    Error::Config(format!(
+                        "Invalid `--jobs` value `{}`, expected a positive number.",
+                        value
+                    ))
+                })?;
+                parsed.jobs = Some(jobs);
+            }
+            OptKind::Help => parsed.help = true,
+            OptKind::Version => parsed.version = true,
+        }
+    }
+
+    // `remove` works with the default prop when no one is given
+    if parsed.command == Some(Command::Remove) && parsed.actions.is_empty() {
+        parsed
+            .actions
+            .push(Action::RemoveProp(DEFAULT_REMOVE_PROP.to_string()));
+    }
+
+    Ok(parsed)
+}
+
+/// Help message generated from the commands and options tables.
+pub fn usage() -> String {
+    let mut usage = format!(
+        "A CLI application to smart replace props in React files.\n\n\
+         Usage: {} [COMMAND] <input> [OPTIONS]\n\n\
+         Use `-` as input to read from stdin and write to stdout. With a folder as input, every\n\
+         source file is processed into the output folder (default: <input>.updated).\n\nCommands:\n",
+        env!("CARGO_PKG_NAME")
+    );
+
+    for command in Command::ALL {
+        usage.push_str(&format!("  {:<10}{}\n", command.name(), command.help()));
+    }
+
+    usage.push_str("\nOptions:\n");
+
+    for opt in OPTIONS {
+        let short = opt
+            .short
+            .map_or("    ".to_string(), |s| format!("-{}, ", s));
+        let value = match opt.value {
+            OptValue::None => String::new(),
+            OptValue::Required(value) => format!(" <{}>", value),
+            OptValue::Optional(value) => format!("[={}]", value),
+        };
+        usage.push_str(&format!(
+            "  {:<42}{}\n",
+            format!("{}--{}{}", short, opt.long, value),
+            opt.help
+        ));
+    }
+
+    usage.push_str("\nPresets:\n");
+
+    for preset in Preset::ALL {
+        usage.push_str(&format!("  {}\n", preset.name()));
+    }
+
+    usage
+}
+
+fn find_option(name: &str) -> Option<&'static Opt> {
+    if let Some(long) = name.strip_prefix("--") {
+        OPTIONS.iter().find(|opt| opt.long == long)
+    } else {
+        let mut chars = name.strip_prefix('-')?.chars();
+        let short = chars.next()?;
+        if chars.next().is_some() {
+            return None;
+        }
+        OPTIONS.iter().find(|opt| opt.short == Some(short))
+    }
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_repeated_and_comma_separated_remove_props() {
+        let parsed = parse(&args(&[
+            "file.jsx",
+            "--remove-prop=data-cy,data-qa",
+            "--remove-prop=data-test",
+            "--remove-prop",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            parsed.actions,
+            vec![
+                Action::RemoveProp("data-cy".to_string()),
+                Action::RemoveProp("data-qa".to_string()),
+                Action::RemoveProp("data-test".to_string()),
+                Action::RemoveProp("data-testid".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_paths_with_spaces_and_dashes() {
+        let parsed = parse(&args(&[
+            "my folder/my--file.jsx",
+            "--output=out--dir/file name.jsx",
+            "--remove-prop",
+        ]))
+        .unwrap();
+
+        assert_eq!(parsed.input, Some(PathBuf::from("my folder/my--file.jsx")));
+        assert_eq!(parsed.output, Some(PathBuf::from("out--dir/file name.jsx")));
+    }
+
+    #[test]
+    fn parse_optional_value_before_the_input() {
+        // An existing path is the input
+        let parsed = parse(&args(&["--remove-prop", "src/lib.rs"])).unwrap();
+
+        assert_eq!(parsed.input, Some(PathBuf::from("src/lib.rs")));
+        assert_eq!(
+            parsed.actions,
+            vec![Action::RemoveProp("data-testid".to_string())]
+        );
+
+        // Other values are taken by the option
+        let parsed = parse(&args(&["--remove-prop", "data-cy", "src/lib.rs"])).unwrap();
+
+        assert_eq!(parsed.input, Some(PathBuf::from("src/lib.rs")));
+        assert_eq!(
+            parsed.actions,
+            vec![Action::RemoveProp("data-cy".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_subcommands() {
+        let parsed = parse(&args(&["remove", "file.jsx", "-o", "out.jsx"])).unwrap();
+        assert_eq!(parsed.command, Some(Command::Remove));
+        assert_eq!(parsed.output, Some(PathBuf::from("out.jsx")));
+        assert_eq!(
+            parsed.actions,
+            vec![Action::RemoveProp("data-testid".to_string())]
+        );
+
+        let parsed = parse(&args(&[
+            "rename",
+            "file.jsx",
+            "--rename-prop",
+            "class=className",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed.actions,
+            vec![Action::RenameProp(
+                "class".to_string(),
+                "className".to_string()
+            )]
+        );
+        assert_eq!(parsed.mode(), Mode::Transform);
+
+        let parsed = parse(&args(&["report", "src", "--format", "csv", "-j", "4"])).unwrap();
+        assert_eq!(parsed.jobs, Some(4));
+        assert_eq!(parsed.mode(), Mode::Report);
+        assert_eq!(parsed.format, Some(ReportFormat::Csv));
+    }
+
+    #[test]
+    fn parse_invalid_args() {
+        assert!(parse(&args(&["file.jsx", "--unknown"])).is_err());
+        assert!(parse(&args(&["file.jsx", "other.jsx"])).is_err());
+        assert!(parse(&args(&["file.jsx", "--output"])).is_err());
+        assert!(parse(&args(&["file.jsx", "--rename-prop", "class"])).is_err());
+        assert!(parse(&args(&["remove", "file.jsx", "--rename-prop", "a=b"])).is_err());
+        assert!(parse(&args(&["file.jsx", "--help=yes"])).is_err());
+        assert!(parse(&args(&["file.jsx", "--preset", "unknown"])).is_err());
+        assert!(parse(&args(&["report", "file.jsx", "--format", "xml"])).is_err());
+        assert!(parse(&args(&["remove", "file.jsx", "--format", "json"])).is_err());
+        assert!(parse(&args(&["file.jsx", "--report", "xml"])).is_err());
+        assert!(parse(&args(&["src", "--jobs", "0"])).is_err());
+        assert!(parse(&args(&["src", "-j", "many"])).is_err());
+        assert!(parse(&args(&["report", "file.jsx", "--report", "json"])).is_err());
+    }
+
+    #[test]
+    fn usage_lists_every_command_and_option() {
+        let usage = usage();
+
+        for command in Command::ALL {
+            assert!(usage.contains(command.name()));
+        }
+        for opt in OPTIONS {
+            assert!(usage.contains(&format!("--{}", opt.long)));
+        }
+        for preset in Preset::ALL {
+            assert!(usage.contains(preset.name()));
+        }
+    }
+}
@@ -1,96 +1,616 @@
-use std::collections::HashMap;
+/// Byte range of a parsed item in the parsed content.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, PartialOrd, Ord, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+}
 
-type Prop = (String, Option<String>);
+use crate::{inline_vec::InlineVec, syntax::Syntax};
+
+/// Props stored inline before spilling to the heap; most elements have fewer.
+const INLINE_PROPS: usize = 6;
+
+/// HTML elements without content nor closing tag.
+const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Value of a prop, raw as written in the source (a slice of the parsed content).
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum PropValue<'a> {
+    /// Prop without value (`disabled`), a truthy boolean.
+    #[default]
+    Boolean,
+    /// Quoted string, quotes included (`"text"`).
+    Literal(&'a str),
+    /// JSX expression, braces included (`{value}`).
+    Expression(&'a str),
+    /// Unquoted value, only valid in HTML (`size=10`).
+    Unquoted(&'a str),
+    /// Spread attribute, braces included (`{...props}`). Spread props have an empty name.
+    Spread(&'a str),
+}
 
+/// A prop of an element, with its location in the parsed content.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Prop<'a> {
+    pub name: &'a str,
+    pub value: PropValue<'a>,
+    /// Span of the whole prop (`name="value"`).
+    pub span: Span,
+    /// Span of the prop name.
+    pub name_span: Span,
+    /// Directives of the syntax of the element.
+    directives: &'static [(&'static str, Directive)],
+    /// Name compared ignoring the case, as the attributes of HTML elements.
+    ignore_case: bool,
+}
+
+impl<'a> Prop<'a> {
+    /// Raw value as written in the source (quotes and braces included). `None` for booleans.
+    pub fn raw_value(&self) -> Option<&'a str> {
+        match self.value {
+            PropValue::Boolean => None,
+            PropValue::Literal(value)
+            | PropValue::Expression(value)
+            | PropValue::Unquoted(value)
+            | PropValue::Spread(value) => Some(value),
+        }
+    }
+
+    /// Value of literal props without quotes.
+    pub fn literal_value(&self) -> Option<&'a str> {
+        match self.value {
+            PropValue::Literal(value) => Some(&value[1..value.len() - 1]),
+            PropValue::Unquoted(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Name of the prop bound by a template directive, without the directive and its
+    /// modifiers (`title` for `:title`, `click` for `@click.stop`). Other props keep their name.
+    pub fn bare_name(&self) -> &'a str {
+        self.directive().map_or(self.name, |(_, _, bare)| bare)
+    }
+
+    /// Span of the bare name in the parsed content.
+    pub fn bare_span(&self) -> Span {
+        match self.directive() {
+            Some((_, prefix, bare)) => {
+                let start = self.name_span.start + prefix.len();
+                Span::new(start, start + bare.len())
+            }
+            None => self.name_span,
+        }
+    }
+
+    /// Whether the prop is the `name` prop. Props bound by a directive of the syntax of their
+    /// element are also the prop of their bare name (`:data-testid` is `data-testid` in Vue),
+    /// and event handlers are the handlers of every directive form (`@click` is `v-on:click`
+    /// and `on:click`).
+    /// Attribute names of HTML elements ignore the case (`DATA-TESTID` is `data-testid`).
+    pub fn matches(&self, name: &str) -> bool {
+        let same = |a: &str, b: &str| a == b || (self.ignore_case && a.eq_ignore_ascii_case(b));
+        if same(self.name, name) {
+            return true;
+        }
+
+        let Some((kind, _, bare)) = self.directive() else {
+            return false;
+        };
+        match directive(&DIRECTIVES, name) {
+            Some((other_kind, _, other_bare)) => kind == other_kind && same(bare, other_bare),
+            None => kind == Directive::Bind && same(bare, name),
+        }
+    }
+
+    fn directive(&self) -> Option<(Directive, &'static str, &'a str)> {
+        directive(self.directives, self.name)
+    }
+
+    /// Shorthand prop, named after the variable it is set to (`{value}` in Svelte and Astro).
+    pub fn is_shorthand(&self) -> bool {
+        self.name_span.start > self.span.start
+    }
+
+    pub fn is_spread(&self) -> bool {
+        matches!(self.value, PropValue::Spread(_))
+    }
+
+    pub fn is_literal(&self) -> bool {
+        matches!(self.value, PropValue::Literal(_) | PropValue::Unquoted(_))
+    }
+}
+
+/// An element opening tag (`<name prop="value">`).
 #[derive(Debug, PartialEq)]
 pub struct Component<'a> {
-    pub name: String,
+    pub name: &'a str,
     pub typo: ComponentType,
+    /// Source of the opening tag.
     pub source: &'a str,
-    props: HashMap<String, Prop>,
+    /// Span of the opening tag in the parsed content.
+    pub span: Span,
+    /// Span of the element name in the parsed content.
+    pub name_span: Span,
+    /// Element without closing tag: written `/>`, or an HTML void element (`<br>`).
+    pub self_closing: bool,
+    props: InlineVec<Prop<'a>, INLINE_PROPS>,
+    /// Directives of the prop names.
+    directives: &'static [(&'static str, Directive)],
+    /// Prop names compared ignoring the case.
+    ignore_case: bool,
 }
 
 impl<'a> Component<'a> {
-    pub fn new(name: String, source: &'a str) -> Self {
-        let typo = ComponentType::from(&name);
+    pub(crate) fn new(name: &'a str, source: &'a str) -> Self {
+        let typo = ComponentType::from(name);
 
         Self {
             name,
             typo,
             source,
-            props: HashMap::new(),
+            span: Span::new(0, source.len()),
+            name_span: Span::default(),
+            self_closing: false,
+            props: InlineVec::new(),
+            directives: &[],
+            ignore_case: false,
         }
     }
 
+    /// Parse the opening tag at the beginning of the content (the `<` is optional).
     pub fn from(content: &'a str) -> Self {
-        let mut f = content.split_inclusive([' ', '>', '\n']);
+        Self::parse_at(content, 0)
+    }
+
+    /// Parse the opening tag starting at `start` (the `<` position). Spans are relative to
+    /// the whole content. Unterminated tags end with the content.
+    pub fn parse_at(content: &'a str, start: usize) -> Self {
+        Self::parse_tag::<false>(content, start, &[])
+    }
+
+    /// Parse the opening tag starting at `start` of a markup of a syntax, with the directives
+    /// of its prop names (see `Syntax::markup`). HTML markup follows the HTML rules: values are
+    /// unquoted up to a whitespace or the tag end (lit `${expression}` bindings included), void
+    /// elements (`<br>`) are self-closing and every element is an HTML element, whatever the
+    /// case of its name.
+    pub(crate) fn parse_markup_at(
+        content: &'a str,
+        start: usize,
+        syntax: Syntax,
+        html: bool,
+    ) -> Self {
+        let directives = directives(syntax, html);
+        match html {
+            true => Self::parse_tag::<true>(content, start, directives),
+            false => Self::parse_tag::<false>(content, start, directives),
+        }
+    }
 
-        let name = f.next().unwrap();
-        let mut component = Self::new(clean_str_to_string(&name), content);
+    fn parse_tag<const HTML: bool>(
+        content: &'a str,
+        start: usize,
+        directives: &'static [(&'static str, Directive)],
+    ) -> Self {
+        let bytes = content.as_bytes();
+        let mut i = start;
 
-        // Avoid the rest of the process if it is an empty component
-        if name.ends_with('>') {
-            return component;
+        if bytes.get(i) == Some(&b'<') {
+            i += 1;
+        }
+
+        let name_start = i;
+        while i < bytes.len() && is_name_char(bytes[i]) {
+            i += 1;
+        }
+
+        let mut component = Self::new(&content[name_start..i], "");
+        component.name_span = Span::new(name_start, i);
+        component.directives = directives;
+        // Every element of the HTML markup is an HTML element, with case-insensitive props
+        if HTML && !component.name.is_empty() {
+            component.typo = ComponentType::HtmlElement;
+            component.ignore_case = true;
         }
 
         //
-        'props_loop: while let Some(prop) = f.next() {
-            let mut prop_c = match prop.trim() {
-                "" => continue 'props_loop,
-                ">" | "/>" => break 'props_loop,
-                _ => String::from(prop),
-            };
-
-            // Special treatment for multiline literal strings (not closed)
-            if prop.matches('`').count() % 2 != 0 {
-                'inner: while let Some(prop_t) = f.next() {
-                    prop_c.push_str(prop_t);
-
-                    if prop_t.contains('`') {
-                        break 'inner;
-                    }
-                }
+        'props_loop: loop {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
             }
 
-            prop_c = clean_str_to_string(&prop_c);
+            match bytes.get(i) {
+                None => break 'props_loop,
+                Some(b'>') => {
+                    i += 1;
+                    break 'props_loop;
+                }
+                Some(b'/') if bytes.get(i + 1) == Some(&b'>') => {
+                    component.self_closing = true;
+                    i += 2;
+                    break 'props_loop;
+                }
+                Some(b'$') if HTML && bytes.get(i + 1) == Some(&b'{') => {
+                    let end = skip_expression(bytes, i + 1);
+                    component.add_prop(Prop {
+                        name: "",
+                        value: PropValue::Spread(&content[i..end]),
+                        span: Span::new(i, end),
+                        name_span: Span::new(i, i),
+                        ..Prop::default()
+                    });
+                    i = end;
+                }
+                Some(b'{') if !HTML => {
+                    let end = skip_expression(bytes, i);
+                    let expression = &content[i..end];
+                    let variable = expression
+                        .strip_prefix('{')
+                        .and_then(|inner| inner.strip_suffix('}'))
+                        .map(str::trim)
+                        .filter(|inner| is_identifier(inner));
+
+                    component.add_prop(match variable {
+                        Some(name) => {
+                            let name_start = i + expression.find(name).unwrap_or_default();
+                            Prop {
+                                name,
+                                value: PropValue::Expression(expression),
+                                span: Span::new(i, end),
+                                name_span: Span::new(name_start, name_start + name.len()),
+                                ..Prop::default()
+                            }
+                        }
+                        None => Prop {
+                            name: "",
+                            value: PropValue::Spread(expression),
+                            span: Span::new(i, end),
+                            name_span: Span::new(i, i),
+                            ..Prop::default()
+                        },
+                    });
+                    i = end;
+                }
+                Some(_) => {
+                    let prop_start = i;
+                    while i < bytes.len() && !is_prop_name_end::<HTML>(bytes, i) {
+                        i += 1;
+                    }
 
-            if let Some((name, value)) = prop_c.split_once('=') {
-                component.add_prop((name.to_string(), Some(value.to_string())));
-            } else {
-                component.add_prop((prop_c, None));
-            }
+                    // Ignore stray chars (e.g. a lonely `/`)
+                    if i == prop_start {
+                        i += 1;
+                        continue 'props_loop;
+                    }
+
+                    let name_span = Span::new(prop_start, i);
+                    let mut j = i;
+                    while j < bytes.len() && bytes[j].is_ascii_whitespace() {
+                        j += 1;
+                    }
 
-            // Break the loop if component ends its props declaration
-            if prop.ends_with('>') {
-                break 'props_loop;
+                    let value = if bytes.get(j) == Some(&b'=') {
+                        j += 1;
+                        while j < bytes.len() && bytes[j].is_ascii_whitespace() {
+                            j += 1;
+                        }
+
+                        let value_start = j;
+                        let value = match bytes.get(j) {
+                            Some(&quote @ (b'"' | b'\'')) => {
+                                j = content[j + 1..]
+                                    .find(quote as char)
+                                    .map_or(bytes.len(), |end| j + 1 + end + 1);
+                                PropValue::Literal(&content[value_start..j])
+                            }
+                            Some(b'{') if !HTML => {
+                                j = skip_expression(bytes, j);
+                                PropValue::Expression(&content[value_start..j])
+                            }
+                            Some(b'$') if HTML && bytes.get(j + 1) == Some(&b'{') => {
+                                j = skip_expression(bytes, j + 1);
+                                PropValue::Expression(&content[value_start..j])
+                            }
+                            _ if HTML => {
+                                while j < bytes.len()
+                                    && !bytes[j].is_ascii_whitespace()
+                                    && bytes[j] != b'>'
+                                {
+                                    j += 1;
+                                }
+                                PropValue::Unquoted(&content[value_start..j])
+                            }
+                            _ => {
+                                while j < bytes.len() && !is_prop_name_end::<HTML>(bytes, j) {
+                                    j += 1;
+                                }
+                                PropValue::Unquoted(&content[value_start..j])
+                            }
+                        };
+
+                        i = j;
+                        value
+                    } else {
+                        PropValue::Boolean
+                    };
+
+                    component.add_prop(Prop {
+                        name: &content[name_span.start..name_span.end],
+                        value,
+                        span: Span::new(prop_start, i),
+                        name_span,
+                        ..Prop::default()
+                    });
+                }
             }
         }
 
+        component.span = Span::new(start, i);
+        component.source = &content[start..i];
+
+        if component.typo == ComponentType::HtmlElement && HTML {
+            component.self_closing |= VOID_ELEMENTS
+                .iter()
+                .any(|void| void.eq_ignore_ascii_case(component.name));
+        }
+
         // dbg!(&component);
         component
     }
 
-    pub fn add_prop(self: &mut Self, (name, value): Prop) -> &mut Self {
-        self.props.insert(name.clone(), (name, value));
+    /// Whether the element is named `name`, ignoring the case for HTML elements (`<DIV>` is a
+    /// `div`).
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name
+            || (self.typo == ComponentType::HtmlElement && self.name.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn add_prop(self: &mut Self, prop: Prop<'a>) -> &mut Self {
+        self.props.push(Prop {
+            directives: self.directives,
+            ignore_case: self.ignore_case,
+            ..prop
+        });
         self
     }
 
-    /* pub fn get_prop(self: &Self, name: &str) -> Option<&Prop> {
-        self.props.get(name)
-    } */
+    /// Props in source order, spread attributes included.
+    pub fn props(&self) -> &[Prop<'a>] {
+        &self.props
+    }
 
-    /// If prop exists, return a prop in a "name=value" string format.
-    pub fn get_raw_prop(self: &Self, name: &str) -> Option<String> {
-        if let Some((name, value)) = self.props.get(name) {
-            if let Some(v) = value {
-                Some(format!("{}={}", name, v))
-            } else {
-                Some(format!("{}", name))
+    /// Prop with the given name. When a prop is repeated, the last one wins (as in JSX).
+    pub fn prop(&self, name: &str) -> Option<&Prop<'a>> {
+        self.props
+            .iter()
+            .rev()
+            .find(|prop| !prop.is_spread() && prop.matches(name))
+    }
+
+    /// If prop exists, return a prop in a "name=value" string format, as written in the source.
+    pub fn get_raw_prop(self: &Self, name: &str) -> Option<&'a str> {
+        self.prop(name).map(|prop| self.raw(prop.span))
+    }
+
+    /// Spread attributes (`{...props}`) of the element opening tag, as raw slices of the source.
+    pub fn spread_props(&self) -> Vec<&'a str> {
+        self.props
+            .iter()
+            .filter(|prop| prop.is_spread())
+            .map(|prop| self.raw(prop.span))
+            .collect()
+    }
+
+    fn raw(&self, span: Span) -> &'a str {
+        &self.source[span.start - self.span.start..span.end - self.span.start]
+    }
+}
+
+/// Template directive of a prop name.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Directive {
+    /// Prop bound to an expression (`:title`, `v-bind:title`, `bind:value`, lit `.value` and
+    /// `?hidden`).
+    Bind,
+    /// Event handler (`@click`, `v-on:click`, `on:click`).
+    Event,
+}
+
+const VUE_DIRECTIVES: [(&str, Directive); 4] = [
+    (":", Directive::Bind),
+    ("v-bind:", Directive::Bind),
+    ("@", Directive::Event),
+    ("v-on:", Directive::Event),
+];
+
+const SVELTE_DIRECTIVES: [(&str, Directive); 2] =
+    [("bind:", Directive::Bind), ("on:", Directive::Event)];
+
+/// Bindings of the `html` tagged templates of lit.
+const LIT_DIRECTIVES: [(&str, Directive); 2] = [(".", Directive::Bind), ("?", Directive::Bind)];
+
+/// Directives of every syntax, for the prop names of the actions (which apply to every file).
+const DIRECTIVES: [(&str, Directive); 8] = [
+    VUE_DIRECTIVES[0],
+    VUE_DIRECTIVES[1],
+    VUE_DIRECTIVES[2],
+    VUE_DIRECTIVES[3],
+    SVELTE_DIRECTIVES[0],
+    SVELTE_DIRECTIVES[1],
+    LIT_DIRECTIVES[0],
+    LIT_DIRECTIVES[1],
+];
+
+/// Directives of the prop names in a markup of a syntax. JSX, HTML documents and Astro have
+/// none.
+fn directives(syntax: Syntax, html: bool) -> &'static [(&'static str, Directive)] {
+    match (syntax, html) {
+        (Syntax::Vue, _) => &VUE_DIRECTIVES,
+        (Syntax::Svelte, _) => &SVELTE_DIRECTIVES,
+        (Syntax::Jsx, true) => &LIT_DIRECTIVES,
+        _ => &[],
+    }
+}
+
+/// Name bound by a prop name of an action with a template directive, the name itself
+/// otherwise.
+pub(crate) fn bare_name(name: &str) -> &str {
+    directive(&DIRECTIVES, name).map_or(name, |(_, _, bare)| bare)
+}
+
+/// Directive of a prop name, with its prefix and the bare name it binds, modifiers (`.stop`,
+/// `|once`) removed.
+fn directive<'n>(
+    directives: &[(&'static str, Directive)],
+    name: &'n str,
+) -> Option<(Directive, &'static str, &'n str)> {
+    directives.iter().find_map(|&(prefix, kind)| {
+        let bound = name.strip_prefix(prefix)?;
+        let bare = bound.split(['.', '|']).next().unwrap_or_default();
+        (!bare.is_empty()).then_some((kind, prefix, bare))
+    })
+}
+
+pub(crate) fn is_name_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_' | b'.' | b':' | b'$')
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn is_prop_name_end<const HTML: bool>(bytes: &[u8], i: usize) -> bool {
+    match bytes[i] {
+        b'=' | b'>' => true,
+        b'{' => !HTML,
+        b'/' => bytes.get(i + 1) == Some(&b'>'),
+        c => c.is_ascii_whitespace(),
+    }
+}
+
+/// Index after the `}` closing the expression opened at `start`, quotes and template literals
+/// aware. Unterminated expressions end with the content.
+pub(crate) fn skip_expression(bytes: &[u8], start: usize) -> usize {
+    enum Context {
+        Braces,
+        Template,
+    }
+
+    let mut stack = vec![];
+    let mut i = start;
+
+    while i < bytes.len() {
+        match (stack.last(), bytes[i]) {
+            (Some(Context::Template), b'\\') => i += 1,
+            (Some(Context::Template), b'`') => {
+                stack.pop();
+            }
+            (Some(Context::Template), b'$') if bytes.get(i + 1) == Some(&b'{') => {
+                stack.push(Context::Braces);
+                i += 1;
             }
-        } else {
-            None
+            (Some(Context::Template), _) => (),
+            (_, b'{') => stack.push(Context::Braces),
+            (_, b'}') => {
+                stack.pop();
+                if stack.is_empty() {
+                    return i + 1;
+                }
+            }
+            (_, b'`') => stack.push(Context::Template),
+            (_, quote @ (b'"' | b'\'')) => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            (_, b'/') if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            (_, b'/') if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            _ => (),
         }
+        i += 1;
     }
+
+    bytes.len()
+}
+
+/// Keys of the object literal spread by a spread attribute, like `'data-testid'` in
+/// `{...(dataTestId && { 'data-testid': dataTestId })}`.
+pub fn spread_object_keys(raw_spread: &str) -> Vec<String> {
+    let inner = raw_spread
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .trim_start()
+        .trim_start_matches("...");
+
+    let object = match inner.find('{') {
+        Some(start) => &inner[start + 1..],
+        None => return vec![],
+    };
+
+    let mut keys = vec![];
+    let mut entry = String::new();
+    let mut depth = 0;
+
+    for c in object.chars() {
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' if depth == 0 => break,
+            '}' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                keys.push(std::mem::take(&mut entry));
+                continue;
+            }
+            _ => (),
+        }
+        entry.push(c);
+    }
+    keys.push(entry);
+
+    keys.iter()
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let key = entry.split_once(':').map_or(entry.as_str(), |(key, _)| key);
+            key.trim().trim_matches(['\'', '"']).to_string()
+        })
+        .collect()
 }
 
 #[derive(Debug, PartialEq)]
@@ -115,37 +635,10 @@ impl ComponentType {
     }
 }
 
-// TODO move to utils and make a ` ` variable as static
-fn clean_str_to_string(value: &str) -> String {
-    let pat: &[_] = &['\n', ' ', '<', '>', '/'];
-    value.trim_matches(pat).to_string()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn clean_str_with_several_symbols() {
-        let component = " \n<span> \n";
-        let component_cleaned = clean_str_to_string(component);
-        assert_eq!(component_cleaned, "span");
-    }
-
-    #[test]
-    fn clean_no_applies_to_cleaned_str() {
-        let component = "span";
-        let component_cleaned = clean_str_to_string(component);
-        assert_eq!(component_cleaned, component);
-    }
-
-    #[test]
-    fn clean_no_applies_to_cleaned_str_with_intermediate_symbols() {
-        let component = "spa<>n";
-        let component_cleaned = clean_str_to_string(component);
-        assert_eq!(component_cleaned, component);
-    }
-
     #[test]
     fn creation_from_an_empty_prop_component_str() {
         let component_str = "<h1>";
@@ -153,7 +646,7 @@ mod tests {
 
         assert_eq!(component.name, "h1");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 0);
+        assert_eq!(component.props().len(), 0);
         assert_eq!(component.source, component_str);
     }
 
@@ -164,7 +657,7 @@ mod tests {
 
         assert_eq!(component.name, "MyComponent");
         assert_eq!(component.typo, ComponentType::ReactComponent);
-        assert_eq!(component.props.len(), 0);
+        assert_eq!(component.props().len(), 0);
         assert_eq!(component.source, component_str);
     }
 
@@ -175,7 +668,7 @@ mod tests {
 
         assert_eq!(component.name, "h1");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 0);
+        assert_eq!(component.props().len(), 0);
         assert_eq!(component.source, component_str);
     }
 
@@ -186,8 +679,9 @@ mod tests {
 
         assert_eq!(component.name, "h1");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 0);
-        assert_eq!(component.source, component_str);
+        assert_eq!(component.props().len(), 0);
+        assert_eq!(component.source, "<h1 />");
+        assert!(component.self_closing);
     }
 
     #[test]
@@ -197,15 +691,11 @@ mod tests {
 
         assert_eq!(component.name, "span");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 1);
-        assert_eq!(component.props.contains_key("aria-checked"), true);
+        assert_eq!(component.props().len(), 1);
+        assert!(component.prop("aria-checked").is_some());
         assert_eq!(
-            component
-                .props
-                .get("aria-checked")
-                .map(|(_, value)| value)
-                .and_then(|value| value.as_ref()),
-            Some(&"\"true\"".to_string())
+            component.prop("aria-checked").and_then(Prop::raw_value),
+            Some("\"true\"")
         );
         assert_eq!(component.source, component_str);
     }
@@ -217,17 +707,13 @@ mod tests {
 
         assert_eq!(component.name, "div");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 3);
-        assert_eq!(component.props.contains_key("id"), true);
-        assert_eq!(component.props.contains_key("className"), true);
-        assert_eq!(component.props.contains_key("href"), true);
+        assert_eq!(component.props().len(), 3);
+        assert!(component.prop("id").is_some());
+        assert!(component.prop("className").is_some());
+        assert!(component.prop("href").is_some());
         assert_eq!(
-            component
-                .props
-                .get("className")
-                .map(|(_, value)| value)
-                .and_then(|value| value.as_ref()),
-            Some(&"{myVariable}".to_string())
+            component.prop("className").and_then(Prop::raw_value),
+            Some("{myVariable}")
         );
         assert_eq!(component.source, component_str);
     }
@@ -239,16 +725,12 @@ mod tests {
 
         assert_eq!(component.name, "div");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 2);
-        assert_eq!(component.props.contains_key("id"), true);
-        assert_eq!(component.props.contains_key("href"), true);
+        assert_eq!(component.props().len(), 2);
+        assert!(component.prop("id").is_some());
+        assert!(component.prop("href").is_some());
         assert_eq!(
-            component
-                .props
-                .get("href")
-                .map(|(_, value)| value)
-                .and_then(|value| value.as_ref()),
-            Some(&"\"#string\"".to_string())
+            component.prop("href").and_then(Prop::raw_value),
+            Some("\"#string\"")
         );
         assert_eq!(component.source, component_str);
     }
@@ -261,16 +743,12 @@ mod tests {
 
         assert_eq!(component.name, "div");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 2);
-        assert_eq!(component.props.contains_key("data-testid"), true);
-        assert_eq!(component.props.contains_key("id"), true);
+        assert_eq!(component.props().len(), 2);
+        assert!(component.prop("data-testid").is_some());
+        assert!(component.prop("id").is_some());
         assert_eq!(
-            component
-                .props
-                .get("data-testid")
-                .map(|(_, value)| value)
-                .and_then(|value| value.as_ref()),
-            Some(&"{`\n    ${dataTestId}-arrow\n   `}".to_string())
+            component.prop("data-testid").and_then(Prop::raw_value),
+            Some("{`\n    ${dataTestId}-arrow\n   `}")
         );
         assert_eq!(component.source, component_str);
     }
@@ -282,8 +760,83 @@ mod tests {
 
         assert_eq!(component.name, "span");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 0);
-        assert_eq!(component.source, component_str);
+        assert_eq!(component.props().len(), 0);
+        assert_eq!(component.source, "<span>");
+    }
+
+    #[test]
+    fn spread_props_of_a_multi_line_component_str() {
+        let component_str = "span\n  src={imgSrc}\n  {...(dataTestId && { 'data-testid': `${dataTestId}-img` })}\n  {...otherProps}\n/>\n{...notAProp}";
+        let component = Component::from(component_str);
+
+        assert_eq!(
+            component.spread_props(),
+            vec![
+                "{...(dataTestId && { 'data-testid': `${dataTestId}-img` })}",
+                "{...otherProps}"
+            ]
+        );
+    }
+
+    #[test]
+    fn creation_at_an_offset_with_spans_and_typed_props() {
+        let content = "return <input id='name' disabled size=10 value={`${a}}`} {...rest} />;";
+        let component = Component::parse_at(content, 7);
+
+        assert_eq!(component.name, "input");
+        assert_eq!(component.span, Span::new(7, 69));
+        assert_eq!(component.name_span, Span::new(8, 13));
+        assert!(component.self_closing);
+        assert_eq!(
+            component
+                .props()
+                .iter()
+                .map(|prop| (prop.name, &content[prop.span.start..prop.span.end]))
+                .collect::<Vec<_>>(),
+            vec![
+                ("id", "id='name'"),
+                ("disabled", "disabled"),
+                ("size", "size=10"),
+                ("value", "value={`${a}}`}"),
+                ("", "{...rest}"),
+            ]
+        );
+        assert_eq!(component.prop("id").unwrap().literal_value(), Some("name"));
+        assert_eq!(
+            component.prop("disabled").unwrap().value,
+            PropValue::Boolean
+        );
+        assert_eq!(
+            component.prop("size").unwrap().value,
+            PropValue::Unquoted("10")
+        );
+        assert_eq!(component.get_raw_prop("value"), Some("value={`${a}}`}"));
+    }
+
+    #[test]
+    fn names_and_values_borrow_the_parsed_content() {
+        let content = "<a b=\"1\" c={2} d e f g h=3 i>";
+        let component = Component::from(content);
+        let within = |value: &str| content.as_bytes().as_ptr_range().contains(&value.as_ptr());
+
+        assert!(within(component.name));
+        assert_eq!(component.props().len(), 8);
+        assert!(component.props().iter().all(|prop| within(prop.name)));
+        assert_eq!(component.prop("h").unwrap().value, PropValue::Unquoted("3"));
+        assert!(within(component.prop("c").unwrap().raw_value().unwrap()));
+    }
+
+    #[test]
+    fn keys_of_spread_objects() {
+        assert_eq!(
+            spread_object_keys("{...(dataTestId && { 'data-testid': dataTestId })}"),
+            vec!["data-testid"]
+        );
+        assert_eq!(
+            spread_object_keys("{...{ id, \"data-cy\": fn(a, b) }}"),
+            vec!["id", "data-cy"]
+        );
+        assert!(spread_object_keys("{...otherProps}").is_empty());
     }
 
     #[test]
@@ -293,16 +846,117 @@ mod tests {
 
         assert_eq!(component.name, "span");
         assert_eq!(component.typo, ComponentType::HtmlElement);
-        assert_eq!(component.props.len(), 1);
-        assert_eq!(component.props.contains_key("disabled"), true);
+        assert_eq!(component.props().len(), 1);
+        assert!(component.prop("disabled").is_some());
+        assert_eq!(component.prop("disabled").and_then(Prop::raw_value), None);
+        assert_eq!(component.source, component_str);
+    }
+
+    #[test]
+    fn match_props_bound_by_template_directives() {
+        let content =
+            "<input :data-testid=\"id\" v-bind:title=\"t\" @click.stop=\"a\" v-on:submit=\"b\">";
+        let component = Component::parse_markup_at(content, 0, Syntax::Vue, false);
+        let names = |name| {
+            component
+                .props()
+                .iter()
+                .filter(|prop| prop.matches(name))
+                .map(|prop| prop.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("data-testid"), vec![":data-testid"]);
+        assert_eq!(names(":title"), vec!["v-bind:title"]);
+        assert_eq!(names("@click"), vec!["@click.stop"]);
+        assert_eq!(names("v-on:submit"), vec!["v-on:submit"]);
+        assert_eq!(names("@submit"), vec!["v-on:submit"]);
+        assert_eq!(names("click"), Vec::<&str>::new());
+
+        let click = component.prop("@click").unwrap();
+        assert_eq!(click.bare_name(), "click");
+        assert_eq!(
+            &content[click.bare_span().start..click.bare_span().end],
+            "click"
+        );
+    }
+
+    #[test]
+    fn match_directives_of_the_syntax_only() {
+        let content = "<input :title=\"t\" bind:value={v} .checked=${c} on:click={f}>";
+        let names = |syntax, html, name| {
+            Component::parse_markup_at(content, 0, syntax, html)
+                .props()
+                .iter()
+                .filter(|prop| prop.matches(name))
+                .map(|prop| prop.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(Syntax::Vue, false, "title"), vec![":title"]);
+        assert_eq!(names(Syntax::Vue, false, "value"), Vec::<&str>::new());
+        assert_eq!(names(Syntax::Svelte, false, "value"), vec!["bind:value"]);
+        assert_eq!(names(Syntax::Svelte, false, "@click"), vec!["on:click"]);
+        assert_eq!(names(Syntax::Svelte, false, "title"), Vec::<&str>::new());
+        assert_eq!(names(Syntax::Jsx, true, "checked"), vec![".checked"]);
+        assert_eq!(names(Syntax::Jsx, true, "title"), Vec::<&str>::new());
+        for (syntax, html) in [(Syntax::Jsx, false), (Syntax::Html, true)] {
+            assert_eq!(names(syntax, html, "title"), Vec::<&str>::new());
+            assert_eq!(names(syntax, html, "value"), Vec::<&str>::new());
+            assert_eq!(names(syntax, html, "on:click"), vec!["on:click"]);
+        }
+    }
+
+    #[test]
+    fn creation_with_shorthand_props_and_svelte_directives() {
+        let content = "<input {value} { disabled } {...rest} bind:checked on:click|once={send}>";
+        let component = Component::parse_markup_at(content, 0, Syntax::Svelte, false);
+
+        let value = component.prop("value").unwrap();
+        assert!(value.is_shorthand());
+        assert_eq!(value.value, PropValue::Expression("{value}"));
+        assert_eq!(value.name_span, Span::new(8, 13));
+        assert_eq!(
+            component.prop("disabled").unwrap().name_span,
+            Span::new(17, 25)
+        );
+        assert_eq!(component.spread_props(), vec!["{...rest}"]);
+
+        assert_eq!(component.prop("checked").unwrap().name, "bind:checked");
+        assert_eq!(component.prop("@click").unwrap().name, "on:click|once");
+        assert_eq!(component.prop("on:click").unwrap().bare_name(), "click");
+    }
+
+    #[test]
+    fn creation_from_html_tags() {
+        let content = "<INPUT type=text value=/a/b/ ?disabled=${a > b} ${ref(input)} data-x={y}>";
+        let component = Component::parse_markup_at(content, 0, Syntax::Jsx, true);
+
+        assert_eq!(component.name, "INPUT");
+        assert_eq!(component.typo, ComponentType::HtmlElement);
+        assert!(component.self_closing);
+        assert!(component.is_named("input"));
+        assert_eq!(component.source, content);
         assert_eq!(
             component
-                .props
-                .get("disabled")
-                .map(|(_, value)| value)
-                .and_then(|value| value.as_ref()),
-            None
+                .props()
+                .iter()
+                .map(|prop| (prop.name, prop.value))
+                .collect::<Vec<_>>(),
+            vec![
+                ("type", PropValue::Unquoted("text")),
+                ("value", PropValue::Unquoted("/a/b/")),
+                ("?disabled", PropValue::Expression("${a > b}")),
+                ("", PropValue::Spread("${ref(input)}")),
+                ("data-x", PropValue::Unquoted("{y}")),
+            ]
         );
-        assert_eq!(component.source, component_str);
+        assert!(component.prop("disabled").is_some());
+        assert!(component.prop("DATA-X").is_some());
+        assert!(component.prop("Type").is_some());
+
+        assert!(!Component::parse_markup_at("<DIV>", 0, Syntax::Html, true).self_closing);
+        assert!(!Component::from("<DIV>").is_named("div"));
+        assert!(Component::from("<div DATA-X='a'>").prop("data-x").is_none());
     }
 }
@@ -1,15 +1,273 @@
-use std::path::PathBuf;
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+};
 
-#[derive(Debug, PartialEq)]
+use crate::{
+    cache::CACHE_FILE_NAME,
+    cli,
+    element_transform::{CustomTransform, ElementTransform},
+    error::Error,
+    inventory::ReportFormat,
+    journal::JOURNAL_FILE_NAME,
+    rules::RuleSet,
+    summary::SummaryFormat,
+};
+
+/// Input/output path standing for stdin/stdout.
+pub const STDIO: &str = "-";
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Action {
     RemoveProp(String),
+    RenameProp(String, String),
+    /// Add a prop with a raw value (e.g. `"\"noopener\""`) when the element does not have it.
+    AddProp(String, String),
+    /// Remove the spread attributes whose object only sets the given prop, like
+    /// `{...(id && { 'data-testid': id })}`.
+    RemoveSpreadProp(String),
+    /// Apply the action only to the elements with the given name.
+    OnElement(String, Box<Action>),
+    /// User-defined transformation.
+    Custom(CustomTransform),
+}
+
+impl Action {
+    pub fn custom(transform: impl ElementTransform + 'static) -> Self {
+        Action::Custom(CustomTransform(Arc::new(transform)))
+    }
+
+    /// Name of the prop the action works on (empty for custom transformations).
+    pub fn prop_name(&self) -> &str {
+        match self {
+            Action::RemoveProp(name) => name,
+            Action::RenameProp(name, _) => name,
+            Action::AddProp(name, _) => name,
+            Action::RemoveSpreadProp(name) => name,
+            Action::OnElement(_, action) => action.prop_name(),
+            Action::Custom(_) => "",
+        }
+    }
+
+    /// Check if two different actions can not be applied together.
+    pub fn conflicts_with(&self, other: &Action) -> bool {
+        let is_removal =
+            |action: &Action| matches!(action, Action::RemoveProp(_) | Action::RemoveSpreadProp(_));
+
+        if let (Action::Custom(_), _) | (_, Action::Custom(_)) = (self.inner(), other.inner()) {
+            return false;
+        }
+
+        self != other
+            && self.prop_name() == other.prop_name()
+            && self.element_name() == other.element_name()
+            && !(is_removal(self.inner()) && is_removal(other.inner()))
+    }
+
+    /// Fingerprint of a custom transformation (see `ElementTransform::fingerprint`).
+    pub(crate) fn fingerprint(&self) -> Option<u64> {
+        match self.inner() {
+            Action::Custom(transform) => Some(transform.0.fingerprint()),
+            _ => None,
+        }
+    }
+
+    fn inner(&self) -> &Action {
+        match self {
+            Action::OnElement(_, action) => action.inner(),
+            action => action,
+        }
+    }
+
+    /// Identifier of the action in check results, like `remove-prop/data-testid` or
+    /// `rename-prop/for@label`.
+    pub fn rule_id(&self) -> String {
+        match self {
+            Action::RemoveProp(name) => format!("remove-prop/{}", name),
+            Action::RenameProp(name, _) => format!("rename-prop/{}", name),
+            Action::AddProp(name, _) => format!("add-prop/{}", name),
+            Action::RemoveSpreadProp(name) => format!("remove-spread-prop/{}", name),
+            Action::OnElement(element, action) => format!("{}@{}", action.rule_id(), element),
+            Action::Custom(transform) => transform.0.name().to_string(),
+        }
+    }
+
+    /// Name of the elements the action is restricted to, if any.
+    pub fn element_name(&self) -> Option<&str> {
+        match self {
+            Action::OnElement(name, _) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in sets of actions for common transformations.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Preset {
+    /// Remove test ids (`data-testid`, `data-cy`, `data-qa`, `data-test`), spread ones included.
+    StripTestIds,
+    /// Remove development only props (`__self`, `__source`, `data-debug`, `onDebug`).
+    StripDevHandlers,
+    /// Rename HTML attributes to their JSX names (`class` to `className`, `for` to `htmlFor`...).
+    HtmlToJsx,
+    /// Add `rel="noopener noreferrer"` to the links without `rel`.
+    A11ySafeLinks,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Self::StripTestIds,
+        Self::StripDevHandlers,
+        Self::HtmlToJsx,
+        Self::A11ySafeLinks,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StripTestIds => "strip-test-ids",
+            Self::StripDevHandlers => "strip-dev-handlers",
+            Self::HtmlToJsx => "html-to-jsx",
+            Self::A11ySafeLinks => "a11y-safe-links",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, Error> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| Error::Config(format!("Unknown preset `{}`.", name)))
+    }
+
+    pub fn actions(&self) -> Vec<Action> {
+        match self {
+            Self::StripTestIds => ["data-testid", "data-cy", "data-qa", "data-test"]
+                .into_iter()
+                .flat_map(|name| {
+                    [
+                        Action::RemoveProp(name.to_string()),
+                        Action::RemoveSpreadProp(name.to_string()),
+                    ]
+                })
+                .collect(),
+            Self::StripDevHandlers => ["__self", "__source", "data-debug", "onDebug"]
+                .into_iter()
+                .map(|name| Action::RemoveProp(name.to_string()))
+                .collect(),
+            Self::HtmlToJsx => [
+                ("class", "className"),
+                ("for", "htmlFor"),
+                ("tabindex", "tabIndex"),
+                ("readonly", "readOnly"),
+                ("maxlength", "maxLength"),
+                ("colspan", "colSpan"),
+                ("rowspan", "rowSpan"),
+                ("contenteditable", "contentEditable"),
+                ("autocomplete", "autoComplete"),
+                ("autofocus", "autoFocus"),
+            ]
+            .into_iter()
+            .map(|(old, new)| Action::RenameProp(old.to_string(), new.to_string()))
+            .collect(),
+            Self::A11ySafeLinks => vec![Action::OnElement(
+                "a".to_string(),
+                Box::new(Action::AddProp(
+                    "rel".to_string(),
+                    "\"noopener noreferrer\"".to_string(),
+                )),
+            )],
+        }
+    }
+}
+
+/// What `run` does with the input once the actions are applied.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Mode {
+    /// Write the updated content into the output file.
+    #[default]
+    Transform,
+    /// Fail when the actions would change the input, without writing anything.
+    Check,
+    /// Print the uses of every tag and prop of the input file or folder.
+    Report,
+    /// Revert the files rewritten in place by the last run, recorded in its journal.
+    Undo,
 }
 
 #[derive(Debug)]
 pub struct Config {
+    /// Input file, or folder whose source files are all processed.
     pub input: PathBuf,
+    /// Output file, or folder mirroring the input folder.
     pub output: PathBuf,
     pub actions: Vec<Action>,
+    pub mode: Mode,
+    pub report_format: ReportFormat,
+    pub summary_format: SummaryFormat,
+    /// Number of files processed at the same time.
+    pub jobs: usize,
+    /// Incremental cache file, to skip the files unchanged since the last run.
+    pub cache_file: Option<PathBuf>,
+    /// Journal of the edits, written by the runs rewriting the input in place and read to
+    /// undo them.
+    pub journal_file: Option<PathBuf>,
+    /// Git ref: only the files of the input folder changed since it are processed.
+    pub changed_since: Option<String>,
+    /// Parse the transformed content again and refuse it when its elements or the props left
+    /// untouched by the actions differ from the input.
+    pub verify: bool,
+    /// Apply the actions again on the transformed content and refuse it when they change it,
+    /// to detect actions that are not idempotent.
+    pub idempotent: bool,
+    /// Write a source map (v3) next to every output file, mapping it back to the input.
+    pub source_map: bool,
+    /// Keep running and process the input files again when they change (see `watch`).
+    pub watch: bool,
+    /// Config file rules of a folder input, resolved for every file.
+    rules: Option<RuleSet>,
+    env: Option<String>,
+}
+
+impl Config {
+    pub fn reads_stdin(&self) -> bool {
+        self.input.as_os_str() == STDIO
+    }
+
+    pub fn writes_stdout(&self) -> bool {
+        self.output.as_os_str() == STDIO
+    }
+
+    /// Whether the input files are rewritten with their updated content.
+    pub fn writes_in_place(&self) -> bool {
+        self.mode == Mode::Transform && self.journal_file.is_some()
+    }
+
+    pub fn reads_folder(&self) -> bool {
+        self.input.is_dir()
+    }
+
+    /// Actions to apply to a file of the input: the config actions, plus the rules of the
+    /// config file applying to the file for a folder input.
+    pub fn actions_for(&self, file: &Path) -> Result<Cow<'_, [Action]>, Error> {
+        match &self.rules {
+            Some(rules) => {
+                let mut actions = self.actions.clone();
+                actions.extend(rules.actions_for(file, self.env.as_deref()));
+                Ok(Cow::Owned(check_actions(actions)?))
+            }
+            None => Ok(Cow::Borrowed(&self.actions)),
+        }
+    }
+
+    /// Output path of a file of the input.
+    pub fn output_for(&self, file: &Path) -> PathBuf {
+        match file.strip_prefix(&self.input) {
+            Ok(relative) if self.reads_folder() => self.output.join(relative),
+            _ => self.output.clone(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -17,6 +275,20 @@ pub struct ConfigBuilder {
     input: Option<PathBuf>,
     output: Option<PathBuf>,
     actions: Vec<Action>,
+    mode: Mode,
+    report_format: ReportFormat,
+    summary_format: SummaryFormat,
+    jobs: Option<usize>,
+    cache: bool,
+    changed_since: Option<String>,
+    verify: bool,
+    idempotent: bool,
+    source_map: bool,
+    watch: bool,
+    config_file: Option<PathBuf>,
+    discover_config: bool,
+    env: Option<String>,
+    error: Option<Error>,
 }
 
 impl ConfigBuilder {
@@ -25,6 +297,20 @@ impl ConfigBuilder {
             input: None,
             output: None,
             actions: vec![],
+            mode: Mode::default(),
+            report_format: ReportFormat::default(),
+            summary_format: SummaryFormat::default(),
+            jobs: None,
+            cache: false,
+            changed_since: None,
+            verify: false,
+            idempotent: false,
+            source_map: false,
+            watch: false,
+            config_file: None,
+            discover_config: true,
+            env: None,
+            error: None,
         }
     }
 
@@ -33,6 +319,11 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn preset(mut self, preset: Preset) -> Self {
+        self.actions.extend(preset.actions());
+        self
+    }
+
     pub fn input(mut self, file: PathBuf) -> Self {
         self.input = Some(file);
         self
@@ -43,58 +334,170 @@ impl ConfigBuilder {
         self
     }
 
-    pub fn parse_args(mut self, args: &[String]) -> Self {
-        if args.first().is_none() {
-            return self;
-        }
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
 
-        self.input = Some(PathBuf::from(&args.first().unwrap()));
+    pub fn report_format(mut self, format: ReportFormat) -> Self {
+        self.report_format = format;
+        self
+    }
 
-        let args_rest = args[1..].join(" ");
-        let options = args_rest.split("--");
+    pub fn summary_format(mut self, format: SummaryFormat) -> Self {
+        self.summary_format = format;
+        self
+    }
 
-        for option in options {
-            match option.trim_end() {
-                opt if opt.starts_with("output") => {
-                    self.output = if let Some((_, file)) = opt.split_once(' ') {
-                        Some(PathBuf::from(file))
-                    } else {
-                        None
-                    };
-                }
-                opt if opt.starts_with("remove-prop") => {
-                    self.actions.push(Action::RemoveProp(parse_value_to_string(
-                        opt,
-                        "data-testid",
-                    )));
-                }
-                _ => (),
-            }
+    /// Number of files processed at the same time (default: the number of CPUs).
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Enable the incremental cache, stored in the input folder (disabled by default).
+    pub fn cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Only process the files of the input folder changed since a git ref.
+    pub fn changed_since(mut self, git_ref: String) -> Self {
+        self.changed_since = Some(git_ref);
+        self
+    }
+
+    /// Verify the transformed content before writing it (disabled by default).
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Check that applying the actions again does not change the output (disabled by default).
+    pub fn idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// Write a source map next to every output file (disabled by default).
+    pub fn source_map(mut self, source_map: bool) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    /// Keep running and process the input again when it changes.
+    pub fn watch(mut self, watch: bool) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Load the rules of a config file instead of looking for one from the input folder.
+    pub fn config_file(mut self, file: PathBuf) -> Self {
+        self.config_file = Some(file);
+        self
+    }
+
+    /// Enable or disable the config file lookup from the input folder (enabled by default).
+    pub fn discover_config(mut self, discover: bool) -> Self {
+        self.discover_config = discover;
+        self
+    }
+
+    /// Environment label used to select the config file rules.
+    pub fn env(mut self, env: String) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Parse command line arguments. Errors are returned later by `build`.
+    pub fn parse_args(self, args: &[String]) -> Self {
+        match cli::parse(args) {
+            Ok(args) => self.args(args),
+            Err(err) => Self {
+                error: Some(err),
+                ..self
+            },
         }
+    }
 
+    /// Apply already parsed command line arguments.
+    pub fn args(mut self, args: cli::Args) -> Self {
+        self.mode = args.mode();
+        self.report_format = args.format.unwrap_or(self.report_format);
+        self.summary_format = args.summary.unwrap_or(self.summary_format);
+        self.jobs = args.jobs.or(self.jobs);
+        self.cache |= args.cache;
+        self.changed_since = args.changed_since.or(self.changed_since);
+        self.verify |= args.verify;
+        self.idempotent |= args.idempotent;
+        self.source_map |= args.source_map;
+        self.watch |= args.watch;
+        self.input = args.input.or(self.input);
+        self.output = args.output.or(self.output);
+        self.actions.extend(args.actions);
+        self.config_file = args.config.or(self.config_file);
+        self.discover_config &= !args.no_config;
+        self.env = args.env.or(self.env);
         self
     }
 
-    pub fn build(self: Self) -> Result<Config, &'static str> {
-        if self.input.is_none() {
-            return Err("Input file is not present.");
+    pub fn build(mut self) -> Result<Config, Error> {
+        if let Some(err) = self.error {
+            return Err(err);
         }
 
-        if self.actions.is_empty() {
-            return Err("Actions are not present.");
+        if self.input.is_none() {
+            return Err(Error::Config("Input file is not present.".to_string()));
         }
 
         let input = self.input.unwrap();
+        let stdin = input.as_os_str() == STDIO;
 
-        if !input.exists() {
-            return Err("Input file not exists.");
+        if !stdin && !input.exists() {
+            return Err(Error::Config("Input file not exists.".to_string()));
         }
 
-        if input.is_dir() {
-            return Err("Input is not a file.");
+        let folder = input.is_dir();
+
+        let config_file = self.config_file.or_else(|| {
+            let folder = match input.parent() {
+                _ if folder => &input,
+                Some(folder) if !stdin && folder.as_os_str() != "" => folder,
+                _ => Path::new("."),
+            };
+
+            self.discover_config
+                .then(|| RuleSet::discover(folder))
+                .flatten()
+        });
+
+        // The rules of a folder are resolved for every file
+        let mut rules = config_file.map(|file| RuleSet::load(&file)).transpose()?;
+
+        if let (false, Some(rules)) = (folder, rules.take()) {
+            self.actions
+                .extend(rules.actions_for(&input, self.env.as_deref()));
+        }
+
+        let needs_actions = !matches!(self.mode, Mode::Report | Mode::Undo);
+        if self.actions.is_empty() && rules.is_none() && needs_actions {
+            return Err(Error::Config("Actions are not present.".to_string()));
         }
 
+        self.actions = check_actions(self.actions)?;
+
         let output = self.output.unwrap_or_else(|| {
+            if stdin {
+                return PathBuf::from(STDIO);
+            }
+
+            if folder {
+                // `src` is mirrored into `src.updated`
+                let input = fs::canonicalize(&input).unwrap_or_else(|_| input.clone());
+                let name = input.file_name().unwrap_or_default().to_string_lossy();
+                return input.with_file_name(format!("{}.updated", name));
+            }
+
             let extension = input
                 .extension()
                 .and_then(|x| x.to_str())
@@ -104,22 +507,97 @@ impl ConfigBuilder {
             input.with_extension(extension)
         });
 
-        if output.is_dir() {
-            return Err("Output is not a file.");
+        if self.watch && stdin {
+            return Err(Error::Config("Stdin can not be watched.".to_string()));
+        }
+
+        if self.mode == Mode::Undo && (stdin || self.watch) {
+            return Err(Error::Config(
+                "Undo requires a file or folder input, without watch.".to_string(),
+            ));
+        }
+
+        if self.changed_since.is_some() && !folder {
+            return Err(Error::Config(
+                "`--changed-since` requires a folder input.".to_string(),
+            ));
         }
 
+        if needs_actions {
+            if folder && (output.is_file() || output.as_os_str() == STDIO) {
+                return Err(Error::Config("Output is not a folder.".to_string()));
+            }
+
+            if !folder && output.is_dir() {
+                return Err(Error::Config("Output is not a file.".to_string()));
+            }
+        }
+
+        let jobs = self
+            .jobs
+            .filter(|jobs| *jobs > 0)
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |jobs| jobs.get()));
+
+        // Folder of the cache and journal files
+        let state_folder = match input.parent() {
+            _ if folder => input.as_path(),
+            Some(parent) => parent,
+            None => Path::new(""),
+        };
+        let cache_file = (self.cache && !stdin).then(|| state_folder.join(CACHE_FILE_NAME));
+
+        let in_place = self.mode == Mode::Transform
+            && !stdin
+            && fs::canonicalize(&input).ok() == fs::canonicalize(&output).ok();
+        if self.source_map && (output.as_os_str() == STDIO || in_place) {
+            return Err(Error::Config(
+                "`--source-map` requires an output other than stdout and the input.".to_string(),
+            ));
+        }
+
+        let journal_file =
+            (in_place || self.mode == Mode::Undo).then(|| state_folder.join(JOURNAL_FILE_NAME));
+
         Ok(Config {
             input,
             output,
             actions: self.actions,
+            mode: self.mode,
+            report_format: self.report_format,
+            summary_format: self.summary_format,
+            jobs,
+            cache_file,
+            journal_file,
+            changed_since: self.changed_since,
+            verify: self.verify,
+            idempotent: self.idempotent,
+            source_map: self.source_map,
+            watch: self.watch,
+            rules,
+            env: self.env,
         })
     }
 }
 
-fn parse_value_to_string(value: &str, default_value: &str) -> String {
-    if let Some((_, value)) = value.split_once(' ') {
-        value.to_string()
-    } else {
-        default_value.to_string()
+/// Remove duplicated actions and fail when different actions target the same prop of the same
+/// elements.
+pub(crate) fn check_actions(actions: Vec<Action>) -> Result<Vec<Action>, Error> {
+    let mut checked: Vec<Action> = Vec::with_capacity(actions.len());
+
+    for action in actions {
+        if checked.contains(&action) {
+            continue;
+        }
+
+        if let Some(a) = checked.iter().find(|a| a.conflicts_with(&action)) {
+            return Err(Error::ActionConflict(format!(
+                "{:?} and {:?} target the same prop",
+                a, action
+            )));
+        }
+
+        checked.push(action);
     }
+
+    Ok(checked)
 }
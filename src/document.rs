@@ -0,0 +1,126 @@
+use crate::component::Component;
+use crate::syntax::Syntax;
+
+/// 1-based line and column (in chars) of an offset in a content.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A parsed content: the source and the elements found in it, in source order.
+#[derive(Debug, PartialEq)]
+pub struct Document<'a> {
+    source: &'a str,
+    components: Vec<Component<'a>>,
+}
+
+impl<'a> Document<'a> {
+    /// Find every element opening tag of the content. Elements nested in prop expressions
+    /// (`icon={<Icon />}`) are included.
+    pub fn parse(source: &'a str) -> Self {
+        Self::parse_as(source, Syntax::Jsx)
+    }
+
+    /// Find every element opening tag of the parts of the content holding elements in the
+    /// given syntax (the `<template>` block of a Vue component...).
+    pub fn parse_as(source: &'a str, syntax: Syntax) -> Self {
+        let bytes = source.as_bytes();
+        let mut components: Vec<Component> = vec![];
+        // Elements whose opening tag contains the current position
+        let mut open: Vec<usize> = vec![];
+
+        let markup = syntax.markup(source);
+        let starts = markup.iter().flat_map(|markup| {
+            let span = markup.span;
+            source[span.start..span.end]
+                .match_indices('<')
+                .map(move |(i, _)| (span.start + i, markup.html))
+        });
+
+        for (i, html) in starts {
+            // Closing tags, comments, comparisons...
+            match bytes.get(i + 1) {
+                Some(c) if c.is_ascii_alphabetic() || *c == b'>' || *c == b'_' => (),
+                _ => continue,
+            }
+
+            while let Some(&last) = open.last() {
+                if components[last].span.end > i {
+                    break;
+                }
+                open.pop();
+            }
+
+            // A `<` in an opening tag only starts an element inside a prop expression
+            if let Some(&last) = open.last() {
+                let in_expression = components[last].props().iter().any(|prop| {
+                    prop.span.contains(i) && prop.name_span.end <= i && !prop.is_literal()
+                });
+
+                if !in_expression {
+                    continue;
+                }
+            }
+
+            open.push(components.len());
+            components.push(Component::parse_markup_at(source, i, syntax, html));
+        }
+
+        Self { source, components }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    pub fn components(&self) -> &[Component<'a>] {
+        &self.components
+    }
+
+    /// Line and column of a byte offset of the source.
+    pub fn location(&self, offset: usize) -> Location {
+        location(self.source, offset)
+    }
+}
+
+/// Line and column of a byte offset of a content.
+pub fn location(content: &str, offset: usize) -> Location {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    Location {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_elements_in_source_order() {
+        let source = "const a = b < c;\nconst el = (\n  <div title=\"a<b\" icon={<Icon size={1} />}>\n    <></>\n  </div>\n);";
+        let document = Document::parse(source);
+
+        assert_eq!(
+            document
+                .components()
+                .iter()
+                .map(|component| component.name)
+                .collect::<Vec<_>>(),
+            vec!["div", "Icon", ""]
+        );
+
+        let icon = &document.components()[1];
+        assert_eq!(icon.source, "<Icon size={1} />");
+        assert_eq!(
+            document.location(icon.span.start),
+            Location {
+                line: 3,
+                column: 26
+            }
+        );
+    }
+}
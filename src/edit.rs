@@ -0,0 +1,372 @@
+use crate::component::{bare_name, spread_object_keys, Component, Prop, PropValue, Span};
+
+/// Replacement of a span of a content. Empty spans are insertions.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Edit {
+    pub span: Span,
+    pub text: String,
+    /// Prop change made by the edit, for the run summary.
+    pub change: Option<Change>,
+}
+
+impl Edit {
+    pub fn new(span: Span, text: impl Into<String>) -> Self {
+        Self {
+            span,
+            text: text.into(),
+            change: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChangeKind {
+    Removed,
+    Renamed,
+    Updated,
+    Added,
+}
+
+impl ChangeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Removed => "removed",
+            Self::Renamed => "renamed",
+            Self::Updated => "updated",
+            Self::Added => "added",
+        }
+    }
+}
+
+impl Change {
+    /// Description of the change, as a finding of the check mode.
+    pub fn message(&self) -> String {
+        let new_value = self.new_value.as_deref().unwrap_or_default();
+
+        match self.kind {
+            ChangeKind::Removed => {
+                format!("Prop `{}` of `<{}>` must be removed", self.prop, self.tag)
+            }
+            ChangeKind::Renamed => format!(
+                "Prop `{}` of `<{}>` must be renamed to `{}`",
+                self.prop, self.tag, new_value
+            ),
+            ChangeKind::Updated => format!(
+                "Prop `{}` of `<{}>` must be set to `{}`",
+                self.prop, self.tag, new_value
+            ),
+            ChangeKind::Added => format!("Prop `{}` must be added to `<{}>`", self.prop, self.tag),
+        }
+    }
+}
+
+/// A prop change of an element.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Identifier of the action making the change.
+    pub rule: String,
+    pub tag: String,
+    pub prop: String,
+    /// Raw value before the change, `None` for added and boolean props.
+    pub old_value: Option<String>,
+    /// New name of a renamed prop, or new raw value of an updated or added prop.
+    pub new_value: Option<String>,
+    /// Span of the prop, or of the element name for added props.
+    pub span: Span,
+}
+
+/// Builder collecting the edits of the actions applied to a content.
+#[derive(Debug, Default)]
+pub struct Edits {
+    edits: Vec<Edit>,
+    /// Name of the element visited and identifier of the action applied, used to describe
+    /// the changes.
+    tag: String,
+    rule: String,
+}
+
+impl Edits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace any span of the content.
+    pub fn replace(&mut self, span: Span, text: &str) -> &mut Self {
+        self.edits.push(Edit::new(span, text));
+        self
+    }
+
+    pub fn remove_prop(&mut self, prop: &Prop) -> &mut Self {
+        let change = self.change(ChangeKind::Removed, prop, None);
+        self.push(prop.span, String::new(), change)
+    }
+
+    /// Rename a prop. Props bound by a template directive keep it, with their modifiers
+    /// (`:data-testid` renamed to `data-qa` becomes `:data-qa`).
+    pub fn rename_prop(&mut self, prop: &Prop, name: &str) -> &mut Self {
+        // `{value}` renamed to `other` becomes `other={value}`
+        if prop.is_shorthand() {
+            let change = self.change(ChangeKind::Renamed, prop, Some(name));
+            let text = format!("{}={}", name, prop.raw_value().unwrap_or_default());
+            return self.push(prop.span, text, change);
+        }
+
+        let span = prop.bare_span();
+        if span == prop.name_span {
+            let change = self.change(ChangeKind::Renamed, prop, Some(name));
+            return self.push(span, name.to_string(), change);
+        }
+
+        let name = bare_name(name);
+        let start = span.start - prop.name_span.start;
+        let renamed = format!(
+            "{}{}{}",
+            &prop.name[..start],
+            name,
+            &prop.name[start + span.len()..]
+        );
+        let change = self.change(ChangeKind::Renamed, prop, Some(&renamed));
+        self.push(span, name.to_string(), change)
+    }
+
+    /// Set the raw value of a prop (quotes or braces included, e.g. `"\"text\""`).
+    pub fn set_prop_value(&mut self, prop: &Prop, value: &str) -> &mut Self {
+        let change = self.change(ChangeKind::Updated, prop, Some(value));
+        if prop.is_shorthand() {
+            return self.push(prop.span, format!("{}={}", prop.name, value), change);
+        }
+
+        let span = Span::new(prop.name_span.end, prop.span.end);
+        self.push(span, format!("={}", value), change)
+    }
+
+    /// Add a prop right after the element name. Without value, the prop is a truthy boolean.
+    /// A prop the element already has, set by a spread object literal or added by a previous
+    /// edit, is never added twice.
+    pub fn add_prop(
+        &mut self,
+        component: &Component,
+        name: &str,
+        value: Option<&str>,
+    ) -> &mut Self {
+        let at = Span::new(component.name_span.end, component.name_span.end);
+        if self.has_prop(component, name, at) {
+            return self;
+        }
+
+        let text = match value {
+            Some(value) => format!(" {}={}", name, value),
+            None => format!(" {}", name),
+        };
+        let change = Change {
+            kind: ChangeKind::Added,
+            rule: self.rule.clone(),
+            tag: component.name.to_string(),
+            prop: name.to_string(),
+            old_value: None,
+            new_value: value.map(str::to_string),
+            span: component.name_span,
+        };
+
+        self.push(at, text, change)
+    }
+
+    /// Set the element the next edits are made on, and the action making them.
+    pub(crate) fn visit(&mut self, component: &Component, rule: &str) {
+        self.tag.clear();
+        self.tag.push_str(component.name);
+        self.rule.clear();
+        self.rule.push_str(rule);
+    }
+
+    fn has_prop(&self, component: &Component, name: &str, at: Span) -> bool {
+        let in_spread = |value: PropValue| match value {
+            PropValue::Spread(raw) => spread_object_keys(raw).iter().any(|key| key == name),
+            _ => false,
+        };
+        // Edits of an element are made while visiting it, after the ones of previous elements
+        let added = self
+            .edits
+            .iter()
+            .rev()
+            .take_while(|edit| edit.span.start >= component.span.start)
+            .filter_map(|edit| edit.change.as_ref().filter(|_| edit.span == at))
+            .any(|change| change.kind == ChangeKind::Added && change.prop == name);
+
+        component.prop(name).is_some()
+            || component.props().iter().any(|prop| in_spread(prop.value))
+            || added
+    }
+
+    fn change(&self, kind: ChangeKind, prop: &Prop, new_value: Option<&str>) -> Change {
+        Change {
+            kind,
+            rule: self.rule.clone(),
+            tag: self.tag.clone(),
+            prop: prop.name.to_string(),
+            old_value: prop.raw_value().map(str::to_string),
+            new_value: new_value.map(str::to_string),
+            span: prop.span,
+        }
+    }
+
+    fn push(&mut self, span: Span, text: String, change: Change) -> &mut Self {
+        self.edits.push(Edit {
+            span,
+            text,
+            change: Some(change),
+        });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    pub(crate) fn into_inner(self) -> Vec<Edit> {
+        self.edits
+    }
+}
+
+/// Apply the edits to the content. Edits overlapping a previous one are discarded.
+pub fn apply_edits(content: &str, mut edits: Vec<Edit>) -> (String, Vec<Edit>) {
+    edits.sort_by_key(|edit| edit.span.start);
+
+    let mut content_updated = String::with_capacity(content.len());
+    let mut applied: Vec<Edit> = Vec::with_capacity(edits.len());
+    let mut last = 0;
+
+    for edit in edits {
+        // Edits are sorted, so a duplicate can only be one of the last edits applied
+        let duplicated = applied
+            .iter()
+            .rev()
+            .take_while(|other| other.span.start == edit.span.start)
+            .any(|other| *other == edit);
+        if edit.span.start < last || duplicated {
+            continue;
+        }
+
+        content_updated.push_str(&content[last..edit.span.start]);
+        content_updated.push_str(&edit.text);
+        last = edit.span.end;
+        applied.push(edit);
+    }
+
+    content_updated.push_str(&content[last..]);
+
+    (content_updated, applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::Syntax;
+
+    #[test]
+    fn apply_sorted_and_overlapping_edits() {
+        let (content, applied) = apply_edits(
+            "<div id='a' class='b'>",
+            vec![
+                Edit::new(Span::new(12, 17), "className"),
+                Edit::new(Span::new(5, 11), ""),
+                Edit::new(Span::new(5, 7), "key"),
+                Edit::new(Span::new(4, 4), " role='c'"),
+            ],
+        );
+
+        assert_eq!(content, "<div role='c'  className='b'>");
+        assert_eq!(applied.len(), 3);
+    }
+
+    #[test]
+    fn apply_duplicated_insertions_once() {
+        let (content, applied) = apply_edits(
+            "<div>",
+            vec![
+                Edit::new(Span::new(4, 4), " a"),
+                Edit::new(Span::new(4, 4), " b"),
+                Edit::new(Span::new(4, 4), " a"),
+            ],
+        );
+
+        assert_eq!(content, "<div a b>");
+        assert_eq!(applied.len(), 2);
+    }
+
+    #[test]
+    fn build_prop_edits() {
+        let content = "<input value='a' disabled>";
+        let component = Component::from(content);
+        let mut edits = Edits::new();
+
+        edits
+            .set_prop_value(component.prop("value").unwrap(), "{value}")
+            .rename_prop(component.prop("disabled").unwrap(), "readOnly")
+            .add_prop(&component, "id", Some("\"name\""))
+            .add_prop(&component, "required", None);
+
+        let (content, _) = apply_edits(content, edits.into_inner());
+        assert_eq!(
+            content,
+            "<input id=\"name\" required value={value} readOnly>"
+        );
+    }
+
+    #[test]
+    fn never_add_a_prop_twice() {
+        let content = "<input value='a' {...{ 'data-cy': cy }}>";
+        let component = Component::from(content);
+        let mut edits = Edits::new();
+
+        edits
+            .add_prop(&component, "value", Some("\"b\""))
+            .add_prop(&component, "data-cy", None)
+            .add_prop(&component, "id", Some("\"name\""))
+            .add_prop(&component, "id", Some("\"other\""));
+
+        assert_eq!(edits.len(), 1);
+        let (content, _) = apply_edits(content, edits.into_inner());
+        assert_eq!(
+            content,
+            "<input id=\"name\" value='a' {...{ 'data-cy': cy }}>"
+        );
+    }
+
+    #[test]
+    fn rename_props_bound_by_template_directives() {
+        let content = "<input :data-testid=\"id\" v-on:click.stop=\"send\">";
+        let component = Component::parse_markup_at(content, 0, Syntax::Vue, false);
+        let mut edits = Edits::new();
+
+        edits
+            .rename_prop(component.prop("data-testid").unwrap(), "data-qa")
+            .rename_prop(component.prop("@click").unwrap(), "@tap");
+
+        let (content, applied) = apply_edits(content, edits.into_inner());
+        assert_eq!(content, "<input :data-qa=\"id\" v-on:tap.stop=\"send\">");
+        assert_eq!(
+            applied[0].change.as_ref().unwrap().new_value.as_deref(),
+            Some(":data-qa")
+        );
+    }
+
+    #[test]
+    fn rename_and_set_shorthand_props() {
+        let content = "<input {value} {checked}>";
+        let component = Component::from(content);
+        let mut edits = Edits::new();
+
+        edits
+            .rename_prop(component.prop("value").unwrap(), "defaultValue")
+            .set_prop_value(component.prop("checked").unwrap(), "{true}");
+
+        let (content, _) = apply_edits(content, edits.into_inner());
+        assert_eq!(content, "<input defaultValue={value} checked={true}>");
+    }
+}
@@ -0,0 +1,60 @@
+use std::{fmt, sync::Arc};
+
+use crate::{component::Component, edit::Edits};
+
+/// User-defined transformation, applied to every element of a content alongside the built-in
+/// actions (see `Action::custom`).
+///
+/// ```
+/// use react_props_replacer::{transform, Action, Component, ElementTransform, Edits};
+///
+/// struct NoAutoFocus;
+///
+/// impl ElementTransform for NoAutoFocus {
+///     fn name(&self) -> &str {
+///         "no-autofocus"
+///     }
+///
+///     fn visit(&self, component: &Component, edits: &mut Edits) -> Result<(), String> {
+///         if let Some(prop) = component.prop("autoFocus") {
+///             edits.remove_prop(prop);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let content = transform("<input autoFocus />", &[Action::custom(NoAutoFocus)]);
+/// assert_eq!(content.unwrap(), "<input  />");
+/// ```
+pub trait ElementTransform: Send + Sync {
+    /// Name of the transformation, used in messages.
+    fn name(&self) -> &str;
+
+    /// Called once for every element found, in source order. An error fails the whole file,
+    /// reported at the element.
+    fn visit(&self, component: &Component, edits: &mut Edits) -> Result<(), String>;
+
+    /// Hash of the code or settings of the transformation, stored by `--cache` with the actions
+    /// of every file: files are processed again when it changes. The default only relies on the
+    /// name, so the cache must be deleted when the transformation changes.
+    fn fingerprint(&self) -> u64 {
+        0
+    }
+}
+
+/// Shared handle to an `ElementTransform`, so it can be stored in an `Action`.
+#[derive(Clone)]
+pub struct CustomTransform(pub Arc<dyn ElementTransform>);
+
+impl fmt::Debug for CustomTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CustomTransform({:?})", self.0.name())
+    }
+}
+
+/// Two handles are equal when they point to the same transformation.
+impl PartialEq for CustomTransform {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use crate::error::Error;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Decoded content of a source file, keeping track of the details needed to write it back
+/// byte-compatible. Line endings need no tracking: the content is kept byte for byte.
+#[derive(Debug, PartialEq)]
+pub struct Source {
+    pub content: String,
+    pub bom: bool,
+}
+
+impl Source {
+    /// Decode raw file bytes. Only UTF-8 (with or without BOM) is supported.
+    pub fn decode(bytes: Vec<u8>, path: &Path) -> Result<Self, Error> {
+        if bytes.starts_with(UTF16_LE_BOM) || bytes.starts_with(UTF16_BE_BOM) {
+            return Err(Error::Parse {
+                path: path.to_path_buf(),
+                line: 1,
+                column: 1,
+                message: "UTF-16 encoded files are not supported".to_string(),
+            });
+        }
+
+        let bom = bytes.starts_with(UTF8_BOM);
+        let bytes = if bom {
+            bytes[UTF8_BOM.len()..].to_vec()
+        } else {
+            bytes
+        };
+
+        let content = String::from_utf8(bytes).map_err(|err| {
+            let bytes = err.as_bytes();
+            let valid = &bytes[..err.utf8_error().valid_up_to()];
+            let line_start = valid.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+
+            Error::Parse {
+                path: path.to_path_buf(),
+                line: valid.iter().filter(|&&b| b == b'\n').count() + 1,
+                column: String::from_utf8_lossy(&valid[line_start..])
+                    .chars()
+                    .count()
+                    + 1,
+                message: "invalid UTF-8 sequence".to_string(),
+            }
+        })?;
+
+        Ok(Self { content, bom })
+    }
+
+    /// Encode an updated content with the same BOM as the original source.
+    pub fn encode(&self, content: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(content.len() + UTF8_BOM.len());
+
+        if self.bom {
+            bytes.extend_from_slice(UTF8_BOM);
+        }
+        bytes.extend_from_slice(content.as_bytes());
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_utf8_with_bom() {
+        let source = Source::decode(
+            b"\xEF\xBB\xBF<div>\r\n</div>\r\n".to_vec(),
+            Path::new("a.jsx"),
+        )
+        .unwrap();
+
+        assert!(source.bom);
+        assert_eq!(source.content, "<div>\r\n</div>\r\n");
+        assert_eq!(
+            source.encode(&source.content),
+            b"\xEF\xBB\xBF<div>\r\n</div>\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn decode_utf8_without_bom() {
+        let source = Source::decode(b"<div>\n</div>".to_vec(), Path::new("a.jsx")).unwrap();
+
+        assert!(!source.bom);
+        assert_eq!(source.encode("<p/>"), b"<p/>".to_vec());
+    }
+
+    #[test]
+    fn decode_invalid_encodings() {
+        let path = Path::new("a.jsx");
+
+        let error = Source::decode(b"<div>\n  <p>\xFF</p>".to_vec(), path).unwrap_err();
+        assert_eq!(error.to_string(), "a.jsx:2:6: invalid UTF-8 sequence");
+
+        assert!(Source::decode(b"\xFF\xFE<\x00d\x00".to_vec(), path).is_err());
+    }
+}
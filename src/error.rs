@@ -0,0 +1,165 @@
+use std::{fmt, io, path::PathBuf};
+
+/// Errors returned by the library. Every variant maps to a stable process exit code.
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid or incomplete configuration.
+    Config(String),
+    /// Failure reading or writing a file.
+    Io { path: PathBuf, source: io::Error },
+    /// A file could not be parsed (e.g. invalid encoding).
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Two or more actions can not be applied together.
+    ActionConflict(String),
+    /// Check mode found content that would be changed by the actions.
+    CheckFailed(PathBuf),
+    /// The transformed content failed the `verify` or `idempotent` checks of the config.
+    VerifyFailed {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A custom transformation (or script rule) failed on an element of a file.
+    TransformFailed {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl Error {
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// Exit code for the process when the error aborts the application.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Config(_) => 2,
+            Self::Io { .. } => 3,
+            Self::Parse { .. } => 4,
+            Self::ActionConflict(_) => 5,
+            Self::CheckFailed(_) => 1,
+            Self::VerifyFailed { .. } => 6,
+            Self::TransformFailed { .. } => 7,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config(message) => write!(f, "{}", message),
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Parse {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+            Self::ActionConflict(message) => write!(f, "Conflicting actions: {}", message),
+            Self::CheckFailed(path) => {
+                write!(
+                    f,
+                    "{}: props would be changed by the actions",
+                    path.display()
+                )
+            }
+            Self::VerifyFailed {
+                path,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "{}:{}:{}: output refused, {}",
+                path.display(),
+                line,
+                column,
+                message
+            ),
+            Self::TransformFailed {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_stable() {
+        assert_eq!(Error::Config("".to_string()).exit_code(), 2);
+        assert_eq!(
+            Error::io("file.jsx", io::Error::from(io::ErrorKind::NotFound)).exit_code(),
+            3
+        );
+        assert_eq!(
+            Error::Parse {
+                path: PathBuf::from("file.jsx"),
+                line: 1,
+                column: 1,
+                message: "".to_string()
+            }
+            .exit_code(),
+            4
+        );
+        assert_eq!(Error::ActionConflict("".to_string()).exit_code(), 5);
+        assert_eq!(Error::CheckFailed(PathBuf::from("file.jsx")).exit_code(), 1);
+        assert_eq!(
+            Error::VerifyFailed {
+                path: PathBuf::from("file.jsx"),
+                line: 1,
+                column: 1,
+                message: "".to_string()
+            }
+            .exit_code(),
+            6
+        );
+        assert_eq!(
+            Error::TransformFailed {
+                path: PathBuf::from("file.jsx"),
+                line: 1,
+                column: 1,
+                message: "".to_string()
+            }
+            .exit_code(),
+            7
+        );
+    }
+
+    #[test]
+    fn display_parse_error_with_location() {
+        let error = Error::Parse {
+            path: PathBuf::from("file.jsx"),
+            line: 3,
+            column: 7,
+            message: "invalid UTF-8 sequence".to_string(),
+        };
+        assert_eq!(error.to_string(), "file.jsx:3:7: invalid UTF-8 sequence");
+    }
+}
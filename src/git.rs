@@ -0,0 +1,135 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::error::Error;
+
+/// Files of a folder changed since a git ref, using the local `git` binary: the files changed
+/// by the commits since the common ancestor of the ref and `HEAD`, the uncommitted changes and
+/// the untracked files not ignored. Deleted files are left out.
+pub fn changed_files(folder: &Path, since: &str) -> Result<HashSet<PathBuf>, Error> {
+    // Never passed to git as an option (`--output=...`)
+    if since.starts_with('-') {
+        return Err(Error::Config(format!("Invalid git ref `{}`.", since)));
+    }
+    let since = git(
+        folder,
+        &[
+            "rev-parse",
+            "--verify",
+            "--end-of-options",
+            &format!("{}^{{commit}}", since),
+        ],
+    )?;
+
+    let base = git(folder, &["merge-base", since.trim(), "HEAD"])?;
+    let base = base.trim();
+
+    let changed = git(
+        folder,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            "--diff-filter=d",
+            "-z",
+            base,
+            "--",
+        ],
+    )?;
+    let untracked = git(
+        folder,
+        &["ls-files", "--others", "--exclude-standard", "-z"],
+    )?;
+
+    Ok(changed
+        .split('\0')
+        .chain(untracked.split('\0'))
+        .filter(|file| !file.is_empty())
+        .map(|file| folder.join(file))
+        .collect())
+}
+
+/// Output of a git command run in the folder.
+fn git(folder: &Path, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(folder)
+        .args(args)
+        .output()
+        .map_err(|err| Error::io("git", err))?;
+
+    if !output.status.success() {
+        return Err(Error::Config(format!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn files_changed_since_a_ref() {
+        let folder = std::env::temp_dir().join("propsreplacer-git-changed");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(folder.join("src")).unwrap();
+
+        let run = |args: &[&str]| git(&folder, args).unwrap();
+        run(&["init", "-q"]);
+        for file in ["src/A.jsx", "src/B.jsx", "src/C.jsx"] {
+            fs::write(folder.join(file), "<div />").unwrap();
+        }
+        run(&["add", "."]);
+        let commit = [
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "-c",
+            "commit.gpgsign=false",
+            "commit",
+            "-q",
+            "-a",
+            "-m",
+        ];
+        run(&[&commit[..], &["initial"]].concat());
+        run(&["tag", "base"]);
+
+        // Committed, uncommitted, deleted and untracked changes
+        fs::write(folder.join("src/A.jsx"), "<div id='a' />").unwrap();
+        run(&[&commit[..], &["a"]].concat());
+        fs::write(folder.join("src/B.jsx"), "<div id='b' />").unwrap();
+        fs::remove_file(folder.join("src/C.jsx")).unwrap();
+        fs::write(folder.join("src/D.jsx"), "<div />").unwrap();
+
+        let src = folder.join("src");
+        let mut files: Vec<_> = changed_files(&src, "base").unwrap().into_iter().collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![src.join("A.jsx"), src.join("B.jsx"), src.join("D.jsx")]
+        );
+
+        assert!(matches!(
+            changed_files(&src, "unknown-ref"),
+            Err(Error::Config(_))
+        ));
+        let output = folder.join("output");
+        assert!(matches!(
+            changed_files(&src, &format!("--output={}", output.display())),
+            Err(Error::Config(_))
+        ));
+        assert!(!output.exists());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
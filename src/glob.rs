@@ -0,0 +1,65 @@
+/// Match a `/` separated path against a glob pattern.
+///
+/// Supported syntax: `*` (any characters but `/`), `?` (one character but `/`) and `**`
+/// (any number of path segments). Patterns without `/` match the file name at any depth.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let pattern = if pattern.contains('/') {
+        pattern.trim_start_matches("./").to_string()
+    } else {
+        format!("**/{}", pattern)
+    };
+
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.trim_start_matches("./").split('/').collect();
+
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            match_segments(&pattern[1..], path)
+                || (!path.is_empty() && match_segments(pattern, &path[1..]))
+        }
+        (Some(p), Some(s)) => {
+            match_segment(p.as_bytes(), s.as_bytes()) && match_segments(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn match_segment(pattern: &[u8], segment: &[u8]) -> bool {
+    match (pattern.first(), segment.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            match_segment(&pattern[1..], segment)
+                || (!segment.is_empty() && match_segment(pattern, &segment[1..]))
+        }
+        (Some(b'?'), Some(_)) => match_segment(&pattern[1..], &segment[1..]),
+        (Some(p), Some(s)) => p == s && match_segment(&pattern[1..], &segment[1..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_wildcards() {
+        assert!(matches("src/*.jsx", "src/Button.jsx"));
+        assert!(!matches("src/*.jsx", "src/components/Button.jsx"));
+        assert!(matches("src/**/*.jsx", "src/Button.jsx"));
+        assert!(matches("src/**/*.jsx", "src/components/ui/Button.jsx"));
+        assert!(matches("src/Butto?.jsx", "src/Button.jsx"));
+        assert!(!matches("src/**/*.jsx", "lib/Button.jsx"));
+    }
+
+    #[test]
+    fn match_file_name_at_any_depth() {
+        assert!(matches("*.test.jsx", "src/components/Button.test.jsx"));
+        assert!(matches("*.test.jsx", "Button.test.jsx"));
+        assert!(!matches("*.test.jsx", "src/Button.jsx"));
+    }
+}
@@ -0,0 +1,81 @@
+use std::ops::Deref;
+
+/// Vector storing up to `N` items inline, without allocating, and spilling to the heap beyond.
+#[derive(Debug, Clone)]
+pub(crate) enum InlineVec<T, const N: usize> {
+    Inline([T; N], usize),
+    Heap(Vec<T>),
+}
+
+impl<T: Copy + Default, const N: usize> InlineVec<T, N> {
+    pub fn new() -> Self {
+        Self::Inline([T::default(); N], 0)
+    }
+
+    pub fn push(&mut self, item: T) {
+        match self {
+            Self::Inline(items, len) if *len < N => {
+                items[*len] = item;
+                *len += 1;
+            }
+            Self::Inline(items, len) => {
+                let mut heap = Vec::with_capacity(N * 2);
+                heap.extend_from_slice(&items[..*len]);
+                heap.push(item);
+                *self = Self::Heap(heap);
+            }
+            Self::Heap(heap) => heap.push(item),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn spilled(&self) -> bool {
+        matches!(self, Self::Heap(_))
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for InlineVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for InlineVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Self::Inline(items, len) => &items[..*len],
+            Self::Heap(heap) => heap,
+        }
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for InlineVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_inline_then_spill_to_the_heap() {
+        let mut items = InlineVec::<u8, 2>::new();
+        items.push(1);
+        items.push(2);
+        assert!(!items.spilled());
+        assert_eq!(&*items, &[1, 2]);
+
+        items.push(3);
+        assert!(items.spilled());
+        assert_eq!(&*items, &[1, 2, 3]);
+        assert_eq!(items, {
+            let mut other = InlineVec::<u8, 2>::new();
+            [1, 2, 3].into_iter().for_each(|item| other.push(item));
+            other
+        });
+    }
+}
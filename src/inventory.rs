@@ -0,0 +1,344 @@
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+
+use crate::{document::Document, error::Error, json::Value};
+
+/// Number of `file:line` examples kept for every tag and prop.
+const MAX_EXAMPLES: usize = 3;
+
+/// Output format of the `report` mode.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum ReportFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(Error::Config(format!(
+                "Unknown report format `{}`, expected `table`, `json` or `csv`.",
+                name
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    uses: usize,
+    examples: Vec<String>,
+}
+
+impl Usage {
+    fn add(&mut self, example: &str) {
+        self.uses += 1;
+        if self.examples.len() < MAX_EXAMPLES {
+            self.examples.push(example.to_string());
+        }
+    }
+
+    fn merge(&mut self, other: Usage) {
+        self.uses += other.uses;
+        self.examples.extend(other.examples);
+        self.examples.truncate(MAX_EXAMPLES);
+    }
+}
+
+#[derive(Debug, Default)]
+struct PropUsage {
+    usage: Usage,
+    /// Literal values (quotes excluded) with their number of uses.
+    values: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Default)]
+struct TagUsage {
+    usage: Usage,
+    props: BTreeMap<String, PropUsage>,
+}
+
+/// Uses of every tag and prop found in a set of documents, sorted by name.
+#[derive(Debug, Default)]
+pub struct Inventory {
+    files: usize,
+    tags: BTreeMap<String, TagUsage>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count the elements and props of a document. `path` is used for the examples.
+    pub fn add(&mut self, path: &Path, document: &Document) {
+        self.files += 1;
+
+        for component in document.components() {
+            let example = |offset| format!("{}:{}", path.display(), document.location(offset).line);
+            let tag = self.tags.entry(component.name.to_string()).or_default();
+            tag.usage.add(&example(component.span.start));
+
+            for prop in component.props().iter().filter(|prop| !prop.is_spread()) {
+                let prop_usage = tag.props.entry(prop.name.to_string()).or_default();
+                prop_usage.usage.add(&example(prop.span.start));
+
+                if let Some(value) = prop.literal_value() {
+                    *prop_usage.values.entry(value.to_string()).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    /// Add the uses of another inventory, its examples coming after the current ones.
+    pub fn merge(&mut self, other: Inventory) {
+        self.files += other.files;
+
+        for (name, other_tag) in other.tags {
+            let tag = self.tags.entry(name).or_default();
+            tag.usage.merge(other_tag.usage);
+
+            for (prop_name, other_prop) in other_tag.props {
+                let prop = tag.props.entry(prop_name).or_default();
+                prop.usage.merge(other_prop.usage);
+
+                for (value, uses) in other_prop.values {
+                    *prop.values.entry(value).or_default() += uses;
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Table => self.table(),
+            ReportFormat::Json => format!("{}\n", self.json()),
+            ReportFormat::Csv => self.csv(),
+        }
+    }
+
+    /// One row per tag followed by one row per prop of the tag.
+    fn rows(&self) -> Vec<[String; 5]> {
+        let mut rows = vec![];
+
+        for (name, tag) in &self.tags {
+            rows.push([
+                name.clone(),
+                String::new(),
+                tag.usage.uses.to_string(),
+                String::new(),
+                tag.usage.examples.join(" "),
+            ]);
+
+            for (prop_name, prop) in &tag.props {
+                rows.push([
+                    name.clone(),
+                    prop_name.clone(),
+                    prop.usage.uses.to_string(),
+                    prop.values.len().to_string(),
+                    prop.usage.examples.join(" "),
+                ]);
+            }
+        }
+
+        rows
+    }
+
+    fn table(&self) -> String {
+        let header = ["TAG", "PROP", "USES", "VALUES", "EXAMPLES"].map(str::to_string);
+        let rows = self.rows();
+        let mut widths = header.clone().map(|h| h.chars().count());
+
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut table = String::new();
+
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = format!(
+                "{:<w0$}  {:<w1$}  {:>w2$}  {:>w3$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                row[4],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+            );
+            table.push_str(line.trim_end());
+            table.push('\n');
+        }
+
+        table.push_str(&format!(
+            "\n{} files, {} tags\n",
+            self.files,
+            self.tags.len()
+        ));
+        table
+    }
+
+    fn csv(&self) -> String {
+        let mut csv = String::from("tag,prop,uses,distinct_values,examples\n");
+
+        for row in self.rows() {
+            let cells: Vec<String> = row.iter().map(|cell| csv_cell(cell)).collect();
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    fn json(&self) -> Value {
+        let usage = |usage: &Usage| {
+            vec![
+                ("uses".to_string(), Value::Number(usage.uses as f64)),
+                (
+                    "examples".to_string(),
+                    Value::Array(
+                        usage
+                            .examples
+                            .iter()
+                            .map(|e| Value::String(e.clone()))
+                            .collect(),
+                    ),
+                ),
+            ]
+        };
+
+        let tags = self
+            .tags
+            .iter()
+            .map(|(name, tag)| {
+                let props = tag
+                    .props
+                    .iter()
+                    .map(|(prop_name, prop)| {
+                        let mut entries =
+                            vec![("name".to_string(), Value::String(prop_name.clone()))];
+                        entries.extend(usage(&prop.usage));
+                        entries.push((
+                            "values".to_string(),
+                            Value::Object(
+                                prop.values
+                                    .iter()
+                                    .map(|(value, uses)| {
+                                        (value.clone(), Value::Number(*uses as f64))
+                                    })
+                                    .collect(),
+                            ),
+                        ));
+                        Value::Object(entries)
+                    })
+                    .collect();
+
+                let mut entries = vec![("name".to_string(), Value::String(name.clone()))];
+                entries.extend(usage(&tag.usage));
+                entries.push(("props".to_string(), Value::Array(props)));
+                Value::Object(entries)
+            })
+            .collect();
+
+        Value::Object(vec![
+            ("files".to_string(), Value::Number(self.files as f64)),
+            ("tags".to_string(), Value::Array(tags)),
+        ])
+    }
+}
+
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory() -> Inventory {
+        let mut inventory = Inventory::new();
+        let mut other = Inventory::new();
+        inventory.add(
+            Path::new("a.jsx"),
+            &Document::parse("<div>\n  <button type=\"submit\" disabled>\n  <button type='button' {...props}>\n</div>"),
+        );
+        other.add(
+            Path::new("b.jsx"),
+            &Document::parse("<button type=\"submit\" onClick={send} />"),
+        );
+        inventory.merge(other);
+        inventory
+    }
+
+    #[test]
+    fn render_table() {
+        assert_eq!(
+            inventory().render(ReportFormat::Table),
+            "TAG     PROP      USES  VALUES  EXAMPLES\n\
+             button               3          a.jsx:2 a.jsx:3 b.jsx:1\n\
+             button  disabled     1       0  a.jsx:2\n\
+             button  onClick      1       0  b.jsx:1\n\
+             button  type         3       2  a.jsx:2 a.jsx:3 b.jsx:1\n\
+             div                  1          a.jsx:1\n\
+             \n2 files, 2 tags\n"
+        );
+    }
+
+    #[test]
+    fn render_csv() {
+        let csv = inventory().render(ReportFormat::Csv);
+        assert!(csv.starts_with("tag,prop,uses,distinct_values,examples\n"));
+        assert!(csv.contains("\nbutton,type,3,2,a.jsx:2 a.jsx:3 b.jsx:1\n"));
+        assert_eq!(csv_cell("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn render_json() {
+        let json = Value::parse(
+            &inventory().render(ReportFormat::Json),
+            Path::new("report.json"),
+        )
+        .unwrap();
+
+        let button = &json.get("tags").and_then(Value::as_array).unwrap()[0];
+        let type_prop = button
+            .get("props")
+            .and_then(Value::as_array)
+            .and_then(|props| {
+                props
+                    .iter()
+                    .find(|p| p.get("name") == Some(&Value::String("type".to_string())))
+            })
+            .unwrap();
+
+        assert_eq!(json.get("files"), Some(&Value::Number(2.0)));
+        assert_eq!(button.get("uses"), Some(&Value::Number(3.0)));
+        assert_eq!(
+            type_prop.get("values"),
+            Some(&Value::Object(vec![
+                ("button".to_string(), Value::Number(1.0)),
+                ("submit".to_string(), Value::Number(2.0)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn unknown_format() {
+        assert_eq!("csv".parse::<ReportFormat>().unwrap(), ReportFormat::Csv);
+        assert!("xml".parse::<ReportFormat>().is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{component::Span, edit::Edit, error::Error, json::Value};
+
+/// Journal file name, created in the input folder (or in the folder of the input file) by the
+/// runs rewriting files in place.
+pub const JOURNAL_FILE_NAME: &str = ".propsreplacer-journal";
+
+/// Version of the journal format, changed when the format changes.
+const VERSION: f64 = 1.0;
+
+/// Edits made by the last run rewriting files in place, to undo them.
+#[derive(Debug, Default, PartialEq)]
+pub struct Journal {
+    pub files: Vec<FileEntry>,
+}
+
+/// Edits made to a file.
+#[derive(Debug, PartialEq)]
+pub struct FileEntry {
+    /// Path relative to the journal folder.
+    pub path: PathBuf,
+    /// Hash of the file bytes written, to detect changes made since.
+    pub hash: u64,
+    /// Edits applied, sorted by span.
+    pub edits: Vec<JournalEdit>,
+}
+
+/// Replacement of the `old` text at a span of the original content by the `new` text.
+#[derive(Debug, PartialEq)]
+pub struct JournalEdit {
+    pub span: Span,
+    pub old: String,
+    pub new: String,
+}
+
+impl JournalEdit {
+    pub fn new(content: &str, edit: &Edit) -> Self {
+        Self {
+            span: edit.span,
+            old: content[edit.span.start..edit.span.end].to_string(),
+            new: edit.text.clone(),
+        }
+    }
+}
+
+impl Journal {
+    /// Load a journal file, `None` when it does not exist.
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(Error::io(path, err)),
+        };
+
+        let invalid = || Error::Parse {
+            path: path.to_path_buf(),
+            line: 1,
+            column: 1,
+            message: "invalid journal".to_string(),
+        };
+
+        let document = Value::parse(&content, path)?;
+        if document.get("version") != Some(&Value::Number(VERSION)) {
+            return Err(invalid());
+        }
+
+        let files = document
+            .get("files")
+            .and_then(Value::as_array)
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|file| FileEntry::from_json(file).ok_or_else(invalid))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Some(Self { files }))
+    }
+
+    /// Add the entries of a later run, replacing the ones of the same files: a file edited by
+    /// both runs is reverted to its content before the later one.
+    pub fn merge(&mut self, later: Journal) {
+        for entry in later.files {
+            match self.files.iter_mut().find(|file| file.path == entry.path) {
+                Some(file) => *file = entry,
+                None => self.files.push(entry),
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let document = Value::Object(vec![
+            ("version".to_string(), Value::Number(VERSION)),
+            (
+                "files".to_string(),
+                Value::Array(self.files.iter().map(FileEntry::to_json).collect()),
+            ),
+        ]);
+
+        fs::write(path, format!("{}\n", document)).map_err(|err| Error::io(path, err))
+    }
+}
+
+impl FileEntry {
+    /// Content before the edits, `None` when the content does not have the edits.
+    pub fn revert(&self, content: &str) -> Option<String> {
+        let mut reverted = String::with_capacity(content.len());
+        let mut last = 0;
+        // Offset of the original content in the edited one
+        let mut shift = 0isize;
+
+        for edit in &self.edits {
+            let start = edit.span.start.checked_add_signed(shift)?;
+            let end = start + edit.new.len();
+            if start < last || content.get(start..end)? != edit.new {
+                return None;
+            }
+
+            reverted.push_str(&content[last..start]);
+            reverted.push_str(&edit.old);
+            last = end;
+            shift += edit.new.len() as isize - edit.span.len() as isize;
+        }
+
+        reverted.push_str(content.get(last..)?);
+        Some(reverted)
+    }
+
+    fn to_json(&self) -> Value {
+        let edits = self
+            .edits
+            .iter()
+            .map(|edit| {
+                Value::Object(vec![
+                    ("start".to_string(), Value::Number(edit.span.start as f64)),
+                    ("end".to_string(), Value::Number(edit.span.end as f64)),
+                    ("old".to_string(), Value::String(edit.old.clone())),
+                    ("new".to_string(), Value::String(edit.new.clone())),
+                ])
+            })
+            .collect();
+
+        Value::Object(vec![
+            (
+                "path".to_string(),
+                Value::String(self.path.display().to_string()),
+            ),
+            (
+                "hash".to_string(),
+                Value::String(format!("{:016x}", self.hash)),
+            ),
+            ("edits".to_string(), Value::Array(edits)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let offset = |edit: &Value, key: &str| match edit.get(key)? {
+            Value::Number(offset) if *offset >= 0.0 => Some(*offset as usize),
+            _ => None,
+        };
+        let text = |edit: &Value, key: &str| edit.get(key)?.as_str().map(str::to_string);
+
+        let edits = value
+            .get("edits")?
+            .as_array()?
+            .iter()
+            .map(|edit| {
+                Some(JournalEdit {
+                    span: Span::new(offset(edit, "start")?, offset(edit, "end")?),
+                    old: text(edit, "old")?,
+                    new: text(edit, "new")?,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            path: PathBuf::from(value.get("path")?.as_str()?),
+            hash: u64::from_str_radix(value.get("hash")?.as_str()?, 16).ok()?,
+            edits,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_load_and_revert_edits() {
+        let original = "<label for=\"a\" data-testid=\"b\">\n  A\n</label>";
+        let content = "<label htmlFor=\"a\" >\n  A\n</label>";
+        let journal = Journal {
+            files: vec![FileEntry {
+                path: PathBuf::from("src/Form.jsx"),
+                hash: 0xcbf2_9ce4_8422_2325,
+                edits: vec![
+                    JournalEdit {
+                        span: Span::new(7, 10),
+                        old: "for".to_string(),
+                        new: "htmlFor".to_string(),
+                    },
+                    JournalEdit {
+                        span: Span::new(15, 30),
+                        old: "data-testid=\"b\"".to_string(),
+                        new: String::new(),
+                    },
+                ],
+            }],
+        };
+
+        let path = std::env::temp_dir().join("propsreplacer-journal-test");
+        journal.save(&path).unwrap();
+        let loaded = Journal::load(&path).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, journal);
+        assert_eq!(loaded.files[0].revert(content).as_deref(), Some(original));
+        assert_eq!(loaded.files[0].revert("<label for=\"a\">"), None);
+        assert_eq!(Journal::load(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn merge_later_runs() {
+        let entry = |path: &str, hash: u64| FileEntry {
+            path: PathBuf::from(path),
+            hash,
+            edits: vec![],
+        };
+        let mut journal = Journal {
+            files: vec![entry("a.jsx", 1), entry("b.jsx", 2)],
+        };
+
+        journal.merge(Journal {
+            files: vec![entry("b.jsx", 3), entry("c.jsx", 4)],
+        });
+        assert_eq!(
+            journal.files,
+            vec![entry("a.jsx", 1), entry("b.jsx", 3), entry("c.jsx", 4)]
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use std::{fmt, iter::Peekable, path::Path, str::CharIndices};
+
+use crate::error::Error;
+
+/// Minimal JSON document model, enough for config files and reports.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// Keys keep the document order.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Parse a JSON document. `path` is only used to locate errors.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, Error> {
+        let mut parser = Parser {
+            content,
+            chars: content.char_indices().peekable(),
+            path,
+        };
+
+        let value = parser.value()?;
+        parser.skip_whitespace();
+
+        match parser.chars.peek() {
+            None => Ok(value),
+            Some(&(offset, _)) => Err(parser.error_at(offset, "unexpected trailing content")),
+        }
+    }
+}
+
+/// Pretty printed document, indented with two spaces.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl Value {
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = "  ".repeat(indent + 1);
+
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Number(value) => write!(f, "{}", value),
+            Value::String(value) => write_string(f, value),
+            Value::Array(values) if values.is_empty() => write!(f, "[]"),
+            Value::Array(values) => {
+                writeln!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    write!(f, "{}", pad)?;
+                    value.write(f, indent + 1)?;
+                    writeln!(f, "{}", if i + 1 < values.len() { "," } else { "" })?;
+                }
+                write!(f, "{}]", "  ".repeat(indent))
+            }
+            Value::Object(entries) if entries.is_empty() => write!(f, "{{}}"),
+            Value::Object(entries) => {
+                writeln!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    write!(f, "{}", pad)?;
+                    write_string(f, key)?;
+                    write!(f, ": ")?;
+                    value.write(f, indent + 1)?;
+                    writeln!(f, "{}", if i + 1 < entries.len() { "," } else { "" })?;
+                }
+                write!(f, "{}}}", "  ".repeat(indent))
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct Parser<'a> {
+    content: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    path: &'a Path,
+}
+
+impl<'a> Parser<'a> {
+    fn error_at(&self, offset: usize, message: &str) -> Error {
+        let before = &self.content[..offset];
+
+        Error::Parse {
+            path: self.path.to_path_buf(),
+            line: before.matches('\n').count() + 1,
+            column: before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+            message: message.to_string(),
+        }
+    }
+
+    fn error(&mut self, message: &str) -> Error {
+        let offset = self.chars.peek().map_or(self.content.len(), |&(i, _)| i);
+        self.error_at(offset, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+
+        match self.chars.next_if(|&(_, c)| c == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error(&format!("expected `{}`", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.skip_whitespace();
+
+        match self.chars.peek().map(|&(_, c)| c) {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Value::String),
+            Some('-' | '0'..='9') => self.number(),
+            Some(_) => self.literal(),
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, Error> {
+        let mut entries = vec![];
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        if self.chars.next_if(|&(_, c)| c == '}').is_some() {
+            return Ok(Value::Object(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, '}')) => return Ok(Value::Object(entries)),
+                Some((offset, _)) => return Err(self.error_at(offset, "expected `,` or `}`")),
+                None => return Err(self.error("unexpected end of file")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        let mut values = vec![];
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        if self.chars.next_if(|&(_, c)| c == ']').is_some() {
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, ']')) => return Ok(Value::Array(values)),
+                Some((offset, _)) => return Err(self.error_at(offset, "expected `,` or `]`")),
+                None => return Err(self.error("unexpected end of file")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        if self.chars.next_if(|&(_, c)| c == '"').is_none() {
+            return Err(self.error("expected a string"));
+        }
+
+        let mut value = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((offset, '\\')) => match self.chars.next() {
+                    Some((_, '"')) => value.push('"'),
+                    Some((_, '\\')) => value.push('\\'),
+                    Some((_, '/')) => value.push('/'),
+                    Some((_, 'b')) => value.push('\u{8}'),
+                    Some((_, 'f')) => value.push('\u{c}'),
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, 'u')) => {
+                        let hex: String = (0..4)
+                            .filter_map(|_| self.chars.next())
+                            .map(|(_, c)| c)
+                            .collect();
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error_at(offset, "invalid unicode escape"))?;
+                        value.push(c);
+                    }
+                    _ => return Err(self.error_at(offset, "invalid escape sequence")),
+                },
+                Some((_, c)) => value.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.chars.peek().map_or(0, |&(i, _)| i);
+        let mut end = start;
+
+        while let Some((i, c)) = self
+            .chars
+            .next_if(|&(_, c)| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            end = i + c.len_utf8();
+        }
+
+        self.content[start..end]
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| self.error_at(start, "invalid number"))
+    }
+
+    fn literal(&mut self) -> Result<Value, Error> {
+        let start = self.chars.peek().map_or(0, |&(i, _)| i);
+        let mut word = String::new();
+
+        while let Some((_, c)) = self.chars.next_if(|&(_, c)| c.is_ascii_alphabetic()) {
+            word.push(c);
+        }
+
+        match word.as_str() {
+            "null" => Ok(Value::Null),
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err(self.error_at(start, "unexpected token")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nested_document() {
+        let value = Value::parse(
+            r#"{ "rules": [ { "action": "remove", "props": ["data-testid", "data-\"cy\""] } ],
+                 "enabled": true, "jobs": -1.5e1, "env": null }"#,
+            Path::new("config.json"),
+        )
+        .unwrap();
+
+        let rule = &value.get("rules").unwrap().as_array().unwrap()[0];
+        assert_eq!(rule.get("action").and_then(Value::as_str), Some("remove"));
+        assert_eq!(
+            rule.get("props"),
+            Some(&Value::Array(vec![
+                Value::String("data-testid".to_string()),
+                Value::String("data-\"cy\"".to_string())
+            ]))
+        );
+        assert_eq!(value.get("enabled"), Some(&Value::Bool(true)));
+        assert_eq!(value.get("jobs"), Some(&Value::Number(-15.0)));
+        assert_eq!(value.get("env"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn print_and_parse_back() {
+        let value = Value::Object(vec![
+            ("name".to_string(), Value::String("a \"b\"\n".to_string())),
+            (
+                "uses".to_string(),
+                Value::Array(vec![Value::Number(3.0), Value::Null]),
+            ),
+            ("props".to_string(), Value::Object(vec![])),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            "{\n  \"name\": \"a \\\"b\\\"\\n\",\n  \"uses\": [\n    3,\n    null\n  ],\n  \"props\": {}\n}"
+        );
+        assert_eq!(
+            Value::parse(&value.to_string(), Path::new("report.json")).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn parse_error_with_location() {
+        let error =
+            Value::parse("{\n  \"rules\": [1,, 2]\n}", Path::new("config.json")).unwrap_err();
+        assert_eq!(error.to_string(), "config.json:2:15: unexpected token");
+    }
+}
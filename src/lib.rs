@@ -1,86 +1,658 @@
+mod cache;
+pub mod cli;
 mod component;
 mod config;
+mod document;
+mod edit;
+mod element_transform;
+mod encoding;
+mod error;
+mod git;
+mod glob;
+mod inline_vec;
+mod inventory;
+mod journal;
+mod json;
+mod parallel;
+mod rules;
+#[cfg(feature = "scripting")]
+mod script;
+mod source_map;
+mod summary;
+mod syntax;
+mod verify;
+mod walk;
+mod watch;
 
-use std::{borrow::Cow, error::Error, fs};
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use component::{Component, ComponentType};
-pub use config::{Action, Config, ConfigBuilder};
+use cache::{Cache, CACHE_FILE_NAME};
+use component::spread_object_keys;
+pub use component::{Component, ComponentType, Prop, PropValue, Span};
+pub use config::{Action, Config, ConfigBuilder, Mode, Preset, STDIO};
+pub use document::{Document, Location};
+pub use edit::Edits;
+use edit::{apply_edits, Edit};
+pub use element_transform::{CustomTransform, ElementTransform};
+use encoding::Source;
+pub use error::Error;
+pub use inventory::{Inventory, ReportFormat};
+use journal::{Journal, JournalEdit};
+pub use summary::SummaryFormat;
+use summary::{FileSummary, RunSummary, Summary};
+pub use syntax::Syntax;
 
+/// Parse a content, finding every element with its props.
 ///
-pub fn run(config: &Config) -> Result<(), Box<dyn Error>> {
-    // Read the file as a string
-    let content = fs::read_to_string(&config.input).expect("Error reading input file.");
+/// ```
+/// let document = react_props_replacer::parse("<button type=\"submit\" disabled>Send</button>");
+/// let button = &document.components()[0];
+///
+/// assert_eq!(button.name, "button");
+/// assert_eq!(button.prop("type").and_then(|prop| prop.literal_value()), Some("submit"));
+/// ```
+pub fn parse(content: &str) -> Document<'_> {
+    Document::parse(content)
+}
 
-    //
-    let content_updated = apply_content_actions(&content, &config.actions);
+/// Apply the actions to a content and return the updated content.
+///
+/// ```
+/// use react_props_replacer::{transform, Action};
+///
+/// let content = transform(
+///     "<div data-testid=\"box\" />",
+///     &[Action::RemoveProp("data-testid".to_string())],
+/// );
+/// assert_eq!(content.unwrap(), "<div  />");
+/// ```
+pub fn transform(content: &str, actions: &[Action]) -> Result<String, Error> {
+    transform_as(content, Syntax::Jsx, actions)
+}
 
-    //
-    fs::write(&config.output, content_updated).expect("Error writing output file.");
+/// Apply the actions to a content of the given syntax (see `Syntax::from_path`) and return the
+/// updated content.
+///
+/// ```
+/// use react_props_replacer::{transform_as, Action, Syntax};
+///
+/// let content = transform_as(
+///     "<template><p :data-testid=\"id\">a</p></template>",
+///     Syntax::Vue,
+///     &[Action::RemoveProp("data-testid".to_string())],
+/// );
+/// assert_eq!(content.unwrap(), "<template><p >a</p></template>");
+/// ```
+pub fn transform_as(content: &str, syntax: Syntax, actions: &[Action]) -> Result<String, Error> {
+    let actions = config::check_actions(actions.to_vec())?;
+    let (content_updated, _, _) = apply_content_edits(content, syntax, &actions, Path::new(STDIO))?;
 
-    println!("Process completed successfully");
+    Ok(content_updated)
+}
 
-    Ok(())
+/// Apply the config actions to the input file and write the result into the output file.
+///
+/// Input and output can be `-` to read from stdin and write to stdout. In that case, the
+/// summary is printed to stderr. Folders are processed file by file, with `config.jobs`
+/// threads, into the mirrored output folder.
+pub fn run(config: &Config) -> Result<(), Error> {
+    match config.mode {
+        Mode::Report => return report(config),
+        Mode::Undo => return undo(config),
+        Mode::Transform | Mode::Check => (),
+    }
+
+    let files = changed_since(config, walk::source_files(&config.input)?)?;
+    run_files(config, &files, &mut Journal::default())
+}
+
+/// Files of the input changed since the `changed_since` git ref of the config, all without it.
+fn changed_since(config: &Config, mut files: Vec<PathBuf>) -> Result<Vec<PathBuf>, Error> {
+    if let Some(since) = &config.changed_since {
+        let changed = git::changed_files(&config.input, since)?;
+        files.retain(|file| changed.contains(file));
+    }
+
+    Ok(files)
 }
 
+/// Run the config, then run it again on the files of the input changed since the last run,
+/// until the process is stopped. Errors of the runs are printed, not returned.
 ///
-fn apply_content_actions(content: &str, actions: &[Action]) -> String {
-    let mut content_by_component = content.split('<'); // TODO try with split_inclusive
+/// Changes are notified by inotify on Linux, and found polling the modification time of the
+/// files elsewhere.
+pub fn watch(config: &Config) -> Result<(), Error> {
+    if config.reads_stdin() {
+        return Err(Error::Config("Stdin can not be watched.".to_string()));
+    }
 
-    let mut content_by_component_updated: Vec<Cow<'_, str>> =
-        vec![Cow::Borrowed(content_by_component.next().unwrap())];
-    let mut component_vector: Vec<Component> = vec![];
+    // Notified from before the first look at the files, so no change is missed
+    let mut watcher = watch::Watcher::new(&config.input);
+    let mut snapshot = watch::Snapshot::default();
+    // Edits of every run since the start, so `undo` reverts the whole session
+    let mut journal = Journal::default();
 
-    //
-    while let Some(raw_component) = content_by_component.next() {
-        // Ignore 'component-lines' with element close char
-        if raw_component.trim_start().starts_with('/') {
-            content_by_component_updated.push(Cow::Borrowed(raw_component));
-            continue;
+    run_changed(config, &mut snapshot, &mut journal)?;
+    eprintln!("Watching {} for changes...", config.input.display());
+
+    loop {
+        if watcher.wait(Duration::MAX) {
+            run_changed(config, &mut snapshot, &mut journal)?;
         }
+    }
+}
 
-        let component = Component::from(raw_component);
-        let mut source_updated = Cow::Borrowed(raw_component);
+/// Run the config on the files of the input changed since the snapshot (all of them for an
+/// empty one). The snapshot is taken before the run, so the files saved during the run are
+/// processed by the next one. Edits made in place are added to the journal. Errors of the run
+/// are printed, not returned.
+fn run_changed(
+    config: &Config,
+    snapshot: &mut watch::Snapshot,
+    journal: &mut Journal,
+) -> Result<(), Error> {
+    let changed = watch::poll_once(snapshot, &config.input)?;
+    if changed.is_empty() {
+        return Ok(());
+    }
 
-        // Apply actions
-        for action in actions {
-            match action {
-                Action::RemoveProp(prop_name) => {
-                    let raw_prop = component.get_raw_prop(&prop_name);
+    let result = match config.mode {
+        Mode::Report => report(config),
+        Mode::Undo => undo(config),
+        Mode::Transform | Mode::Check => changed_since(config, changed).and_then(|files| {
+            let result = run_files(config, &files, journal);
+            // The rewrite of the files in place is not a change to process again
+            if config.writes_in_place() {
+                snapshot.refresh(&files);
+            }
+            result
+        }),
+    };
 
-                    if component.typo == ComponentType::HtmlElement && raw_prop.is_some() {
-                        source_updated = Cow::Owned(source_updated.replace(&raw_prop.unwrap(), ""));
-                    }
+    if let Err(err) = result {
+        eprintln!("Application error: {}", err);
+    }
+    Ok(())
+}
+
+/// Apply the config actions to some files of the input. The edits made in place are added to
+/// the journal of the previous runs (empty for a single run), saved when the run made some.
+fn run_files(config: &Config, files: &[PathBuf], journal: &mut Journal) -> Result<(), Error> {
+    // Check mode never writes the content, stdout is free for the summary
+    let log = |message: &str| {
+        if config.mode == Mode::Transform && config.writes_stdout() {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    };
+
+    let mut cache = config.cache_file.as_deref().map(Cache::load);
+    let mut run_journal = Journal::default();
+    let results = parallel::map(files, config.jobs, |file| {
+        run_file(config, file, cache.as_ref())
+    });
+
+    // Results are aggregated in file order, the first error wins over check failures
+    let mut summary = RunSummary::default();
+    let mut failure: Option<Error> = None;
+
+    for (file, result) in files.iter().zip(results) {
+        let key = state_key(config.cache_file.as_deref(), file);
+
+        match result {
+            Ok(FileRun::Processed(file_summary, entry, journal_entry)) => {
+                summary.add_file(file_summary);
+
+                if let Some(journal_entry) = journal_entry {
+                    run_journal.files.push(journal_entry);
+                }
+
+                if config.mode == Mode::Check && entry.changed && failure.is_none() {
+                    failure = Some(Error::CheckFailed(file.clone()));
+                }
+                if let Some(cache) = &mut cache {
+                    cache.insert(key, entry);
                 }
             }
+            Ok(FileRun::Skipped) => summary.add_skipped(file, "unchanged since the last run"),
+            Err(err) => {
+                summary.add_error(file, &err);
+
+                if matches!(failure, None | Some(Error::CheckFailed(_))) {
+                    failure = Some(err);
+                }
+                if let Some(cache) = &mut cache {
+                    cache.remove(key);
+                }
+            }
+        }
+    }
+
+    if let (Some(cache), Some(cache_file)) = (&cache, &config.cache_file) {
+        if let Err(err) = cache.save(cache_file) {
+            failure.get_or_insert(err);
+        }
+    }
+
+    // A run without edits keeps the journal of the last run that made some, to undo
+    if let (false, Some(journal_file)) = (run_journal.files.is_empty(), &config.journal_file) {
+        journal.merge(run_journal);
+        if let Err(err) = journal.save(journal_file) {
+            failure.get_or_insert(err);
+        }
+    }
+
+    log(&summary.render(config.summary_format));
+
+    match failure {
+        Some(err) => Err(err),
+        None => {
+            if config.mode == Mode::Transform && config.summary_format == SummaryFormat::Text {
+                log("Process completed successfully");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Outcome of the actions on a file of the input.
+enum FileRun {
+    /// Summary, cache entry and journal of the edits, for the files rewritten in place.
+    Processed(FileSummary, cache::Entry, Option<journal::FileEntry>),
+    /// Same content and actions as in the last run, and nothing to do.
+    Skipped,
+}
+
+/// Apply the actions to a file of the input, unless the cache tells its last run is still
+/// valid: same content, same actions, output, mode and checks of the output, and no change to
+/// check or output written.
+fn run_file(config: &Config, file: &Path, cache: Option<&Cache>) -> Result<FileRun, Error> {
+    let bytes = read_bytes(file)?;
+    let actions = config.actions_for(file)?;
+    let output = config.output_for(file);
+
+    let fingerprints: Vec<u64> = actions.iter().filter_map(Action::fingerprint).collect();
+    let entry = cache::Entry {
+        content: cache::hash(&bytes),
+        actions: cache::hash(
+            format!(
+                "{:?} {:?} {} {} {:?} {} {}",
+                actions,
+                fingerprints,
+                output.display(),
+                config.source_map,
+                config.mode,
+                config.verify,
+                config.idempotent
+            )
+            .as_bytes(),
+        ),
+        changed: false,
+    };
+
+    let cache_key = state_key(config.cache_file.as_deref(), file);
+    if let Some(cached) = cache.and_then(|cache| cache.get(cache_key)) {
+        let up_to_date = match config.mode {
+            Mode::Transform => output.exists(),
+            Mode::Check | Mode::Report | Mode::Undo => !cached.changed,
+        };
+
+        if up_to_date && (cached.content, cached.actions) == (entry.content, entry.actions) {
+            return Ok(FileRun::Skipped);
+        }
+    }
+
+    let source = Source::decode(bytes, file)?;
+    let syntax = Syntax::from_path(file);
+    let (content_updated, applied, components) =
+        apply_content_edits(&source.content, syntax, &actions, file)?;
+
+    let journal_entry = config
+        .journal_file
+        .as_deref()
+        .filter(|_| !applied.is_empty())
+        .map(|journal_file| journal::FileEntry {
+            path: state_key(Some(journal_file), file).to_path_buf(),
+            hash: cache::hash(&source.encode(&content_updated)),
+            edits: applied
+                .iter()
+                .map(|edit| JournalEdit::new(&source.content, edit))
+                .collect(),
+        });
+    let map =
+        (config.source_map && config.mode == Mode::Transform && source_map::is_linkable(&output))
+            .then(|| {
+                let folder = output.parent().unwrap_or(Path::new(""));
+                let name = output.file_name().unwrap_or_default().to_string_lossy();
+                let input = source_map::relative_path(folder, file);
+                source_map::source_map(&source.content, &applied, &name, &input)
+            });
+    let summary = Summary {
+        components,
+        changes: applied.into_iter().filter_map(|edit| edit.change).collect(),
+    };
+
+    if config.verify && config.mode == Mode::Transform && content_updated != source.content {
+        verify::verify(&source.content, &content_updated, syntax, &summary.changes).map_err(
+            |mismatch| {
+                let location = document::location(&source.content, mismatch.offset);
+                Error::VerifyFailed {
+                    path: file.to_path_buf(),
+                    line: location.line,
+                    column: location.column,
+                    message: mismatch.message,
+                }
+            },
+        )?;
+    }
+
+    if config.idempotent && content_updated != source.content {
+        check_idempotent(&content_updated, syntax, &actions, &output)?;
+    }
+
+    match config.mode {
+        Mode::Transform if config.writes_stdout() => {
+            let mut stdout = io::stdout().lock();
+            stdout
+                .write_all(&source.encode(&content_updated))
+                .and_then(|_| stdout.flush())
+                .map_err(|err| Error::io(&output, err))?;
+        }
+        Mode::Transform => {
+            if let (true, Some(folder)) = (config.reads_folder(), output.parent()) {
+                fs::create_dir_all(folder).map_err(|err| Error::io(folder, err))?;
+            }
+
+            let mut content = Cow::Borrowed(content_updated.as_str());
+            if let Some(map) = &map {
+                let map_file = source_map::map_path(&output);
+                fs::write(&map_file, format!("{}\n", map))
+                    .map_err(|err| Error::io(&map_file, err))?;
+
+                let content = content.to_mut();
+                if !content.is_empty() && !content.ends_with('\n') {
+                    content.push('\n');
+                }
+                content.push_str(&source_map::url_comment(&map_file));
+            }
+
+            fs::write(&output, source.encode(&content)).map_err(|err| Error::io(&output, err))?;
+        }
+        Mode::Check | Mode::Report | Mode::Undo => (),
+    }
+
+    Ok(FileRun::Processed(
+        FileSummary::new(file, &source.content, summary),
+        cache::Entry {
+            changed: content_updated != source.content,
+            ..entry
+        },
+        journal_entry,
+    ))
+}
+
+/// Revert the files rewritten in place by the last run, from its journal. Nothing is reverted
+/// when one of the files changed since the run.
+fn undo(config: &Config) -> Result<(), Error> {
+    let journal_file = config
+        .journal_file
+        .as_deref()
+        .ok_or_else(|| Error::Config("No journal to undo.".to_string()))?;
+    let journal = Journal::load(journal_file)?.ok_or_else(|| {
+        Error::Config(format!(
+            "No run to undo, {} not found.",
+            journal_file.display()
+        ))
+    })?;
+    let folder = journal_file.parent().unwrap_or(Path::new(""));
+
+    // Every file is checked before the first one is written
+    let mut reverted = Vec::with_capacity(journal.files.len());
+    for entry in &journal.files {
+        let path = folder.join(&entry.path);
+        let changed = || {
+            Error::Config(format!(
+                "{} changed since the last run, nothing was undone.",
+                path.display()
+            ))
+        };
+
+        let bytes = read_bytes(&path)?;
+        if cache::hash(&bytes) != entry.hash {
+            return Err(changed());
         }
 
-        //
-        content_by_component_updated.push(source_updated);
+        let source = Source::decode(bytes, &path)?;
+        let content = entry.revert(&source.content).ok_or_else(changed)?;
+        reverted.push((path, source.encode(&content)));
+    }
 
-        // Only for tracking purposes
-        component_vector.push(component);
+    for (path, bytes) in &reverted {
+        fs::write(path, bytes).map_err(|err| Error::io(path, err))?;
     }
+    fs::remove_file(journal_file).map_err(|err| Error::io(journal_file, err))?;
 
-    let content_updated = content_by_component_updated.join("<");
+    // Reverted files have their cached content again, but not their output anymore
+    let cache_file = folder.join(CACHE_FILE_NAME);
+    if cache_file.exists() {
+        let mut cache = Cache::load(&cache_file);
+        for entry in &journal.files {
+            cache.remove(&entry.path);
+        }
+        cache.save(&cache_file)?;
+    }
 
+    let edits: usize = journal.files.iter().map(|entry| entry.edits.len()).sum();
     println!(
-        "\n------------------\nSUMMARY: \n\t* Number of components found: {}",
-        component_vector.len()
+        "Undone {} edit(s) in {} file(s)",
+        edits,
+        journal.files.len()
     );
 
-    // dbg!(component_vector);
+    Ok(())
+}
+
+/// Fail when applying the actions again changes the transformed content.
+fn check_idempotent(
+    content: &str,
+    syntax: Syntax,
+    actions: &[Action],
+    output: &Path,
+) -> Result<(), Error> {
+    let (content_again, applied, _) = apply_content_edits(content, syntax, actions, output)?;
+    if content_again == content {
+        return Ok(());
+    }
+
+    let (offset, change) = match applied.iter().find_map(|edit| edit.change.as_ref()) {
+        Some(change) => (change.span.start, change.message()),
+        None => {
+            let offset = content
+                .char_indices()
+                .zip(content_again.chars())
+                .find(|((_, c), other)| c != other)
+                .map_or(content.len().min(content_again.len()), |((i, _), _)| i);
+            (offset, "content changed".to_string())
+        }
+    };
+    let location = document::location(content, offset);
+
+    Err(Error::VerifyFailed {
+        path: output.to_path_buf(),
+        line: location.line,
+        column: location.column,
+        message: format!("actions not idempotent, a second run reports: {}", change),
+    })
+}
+
+/// Path of a file in the cache or the journal, relative to the folder of their file.
+fn state_key<'a>(state_file: Option<&Path>, file: &'a Path) -> &'a Path {
+    state_file
+        .and_then(Path::parent)
+        .and_then(|folder| file.strip_prefix(folder).ok())
+        .unwrap_or(file)
+}
+
+/// Print the inventory of the tags and props of the input file, or of every source file of
+/// the input folder. Files that can not be read are skipped with a warning.
+fn report(config: &Config) -> Result<(), Error> {
+    let files = walk::source_files(&config.input)?;
+    let inventories = parallel::map(&files, config.jobs, |file| {
+        read_source(file).map(|source| {
+            let mut inventory = Inventory::new();
+            inventory.add(
+                file,
+                &Document::parse_as(&source.content, Syntax::from_path(file)),
+            );
+            inventory
+        })
+    });
+
+    let mut inventory = Inventory::new();
+
+    for file_inventory in inventories {
+        match file_inventory {
+            Ok(file_inventory) => inventory.merge(file_inventory),
+            Err(err) => eprintln!("Skipped {}", err),
+        }
+    }
+
+    print!("{}", inventory.render(config.report_format));
+
+    Ok(())
+}
 
-    content_updated
+/// Read a file, or stdin for `-`, keeping its BOM and line endings untouched.
+fn read_source(path: &Path) -> Result<Source, Error> {
+    Source::decode(read_bytes(path)?, path)
+}
+
+fn read_bytes(path: &Path) -> Result<Vec<u8>, Error> {
+    if path.as_os_str() == STDIO {
+        let mut bytes = vec![];
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|err| Error::io(path, err))?;
+        Ok(bytes)
+    } else {
+        fs::read(path).map_err(|err| Error::io(path, err))
+    }
+}
+
+/// Apply the actions to the content, returning the updated content, the edits applied in
+/// source order and the number of elements found. `path` locates the failures of the custom
+/// transformations.
+fn apply_content_edits(
+    content: &str,
+    syntax: Syntax,
+    actions: &[Action],
+    path: &Path,
+) -> Result<(String, Vec<Edit>, usize), Error> {
+    let document = Document::parse_as(content, syntax);
+    let mut edits = Edits::new();
+    let rules: Vec<String> = actions.iter().map(Action::rule_id).collect();
+
+    //
+    for component in document.components() {
+        for (action, rule) in actions.iter().zip(&rules) {
+            edits.visit(component, rule);
+            action_edits(component, action, &mut edits).map_err(|message| {
+                let location = document.location(component.span.start);
+                Error::TransformFailed {
+                    path: path.to_path_buf(),
+                    line: location.line,
+                    column: location.column,
+                    message,
+                }
+            })?;
+        }
+    }
+
+    let (content_updated, applied) = apply_edits(content, edits.into_inner());
+
+    Ok((content_updated, applied, document.components().len()))
+}
+
+/// Edits needed to apply an action to a component. Fails with the message of a failing custom
+/// transformation.
+fn action_edits(component: &Component, action: &Action, edits: &mut Edits) -> Result<(), String> {
+    let is_html = component.typo == ComponentType::HtmlElement;
+    let props = component
+        .props()
+        .iter()
+        .filter(|prop| !prop.is_spread() && prop.matches(action.prop_name()));
+
+    match action {
+        Action::RemoveProp(_) if is_html => {
+            props.for_each(|prop| {
+                edits.remove_prop(prop);
+            });
+        }
+        Action::RenameProp(_, new_name) if is_html => {
+            props.for_each(|prop| {
+                edits.rename_prop(prop, new_name);
+            });
+        }
+        Action::AddProp(prop_name, value) if is_html => {
+            edits.add_prop(component, prop_name, Some(value));
+        }
+        Action::RemoveSpreadProp(prop_name) if is_html => {
+            for prop in component.props() {
+                if let PropValue::Spread(raw) = &prop.value {
+                    if spread_object_keys(raw) == [prop_name.as_str()] {
+                        edits.remove_prop(prop);
+                    }
+                }
+            }
+        }
+        Action::OnElement(name, action) if component.is_named(name) => {
+            return action_edits(component, action, edits);
+        }
+        Action::Custom(transform) => {
+            return transform.0.visit(component, edits).map_err(|message| {
+                format!(
+                    "`{}` failed on <{}>: {}",
+                    transform.0.name(),
+                    component.name,
+                    message
+                )
+            });
+        }
+        _ => (),
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Apply the actions to every component found in the JSX content.
+    fn apply_content_actions(content: &str, actions: &[Action]) -> (String, Summary) {
+        let (content_updated, applied, components) =
+            apply_content_edits(content, Syntax::Jsx, actions, Path::new("")).unwrap();
+
+        (
+            content_updated,
+            Summary {
+                components,
+                changes: applied.into_iter().filter_map(|edit| edit.change).collect(),
+            },
+        )
+    }
+
     #[test]
     fn update_with_remove_action_one_line_content_wo_effect() {
         let content = "import styles from './styles.scss';";
-        let content_result =
+        let (content_result, _) =
             apply_content_actions(content, &[Action::RemoveProp("data-testid".to_string())]);
         assert_eq!(content, content_result);
     }
@@ -88,7 +660,7 @@ mod tests {
     #[test]
     fn update_with_remove_action_one_line_content() {
         let content = "import styles from './styles.scss'; function MyComponent { return (<div><span/><h1 data-testid='test_id' >Main title</h1></div>);}";
-        let content_result =
+        let (content_result, _) =
             apply_content_actions(content, &[Action::RemoveProp("data-testid".to_string())]);
         assert_ne!(content, content_result);
         assert_eq!(content_result.matches("data-testid").count(), 0);
@@ -97,7 +669,7 @@ mod tests {
     #[test]
     fn update_with_remove_action_multi_line_content() {
         let content = "import styles from './styles.scss';\n\n function MyComponent {\n  return (\n    <div>      <span/>\n      <h1 data-testid='test_id' >Main title</h1>\n    </div>);}\n";
-        let content_result =
+        let (content_result, _) =
             apply_content_actions(content, &[Action::RemoveProp("data-testid".to_string())]);
         assert_ne!(content, content_result);
         assert_eq!(content_result.matches("data-testid").count(), 0);
@@ -106,7 +678,7 @@ mod tests {
     #[test]
     fn update_with_remove_action_multi_line_and_multi_prop_content() {
         let content = "import styles from './styles.scss';\n\n function MyComponent {\n  return (\n    <div>      <span id='span_id' data-testid='span_test_id'/>\n      <h1 data-testid='h1_test_id' >Main title</h1>\n    </div>);}\n";
-        let content_result =
+        let (content_result, _) =
             apply_content_actions(content, &[Action::RemoveProp("data-testid".to_string())]);
         assert_ne!(content, content_result);
         assert_eq!(content_result.matches("data-testid").count(), 0);
@@ -114,11 +686,154 @@ mod tests {
         assert_eq!(content_result.contains("<h1  >Main title</h1>"), true);
     }
 
+    #[test]
+    fn update_with_rename_action_multi_prop_content() {
+        let content = "<div class='wrapper'><label class=\"bold\" for='name'>Name</label><input id='name' class /></div>";
+        let (content_result, _) = apply_content_actions(
+            content,
+            &[
+                Action::RenameProp("class".to_string(), "className".to_string()),
+                Action::RenameProp("for".to_string(), "htmlFor".to_string()),
+            ],
+        );
+        assert_eq!(
+            content_result,
+            "<div className='wrapper'><label className=\"bold\" htmlFor='name'>Name</label><input id='name' className /></div>"
+        );
+    }
+
+    #[test]
+    fn update_with_add_action() {
+        let content = "<p><a href='/'>Home</a><a rel='me' href='/me'>Me</a><Link href='/' /></p>";
+        let (content_result, _) = apply_content_actions(
+            content,
+            &[Action::AddProp(
+                "rel".to_string(),
+                "\"noopener\"".to_string(),
+            )],
+        );
+        assert_eq!(
+            content_result,
+            "<p rel=\"noopener\"><a rel=\"noopener\" href='/'>Home</a><a rel='me' href='/me'>Me</a><Link href='/' /></p>"
+        );
+    }
+
+    #[test]
+    fn update_with_remove_spread_action() {
+        let content =
+            "<div {...(id && { 'data-testid': id })} {...{ 'data-testid': id, title }} {...props}>";
+        let (content_result, _) = apply_content_actions(
+            content,
+            &[Action::RemoveSpreadProp("data-testid".to_string())],
+        );
+        assert_eq!(
+            content_result,
+            "<div  {...{ 'data-testid': id, title }} {...props}>"
+        );
+    }
+
+    #[test]
+    fn update_with_element_scoped_action() {
+        let content = "<div><label for='name'>Name</label><output for='name'/></div>";
+        let (content_result, _) = apply_content_actions(
+            content,
+            &[Action::OnElement(
+                "label".to_string(),
+                Box::new(Action::RenameProp("for".to_string(), "htmlFor".to_string())),
+            )],
+        );
+        assert_eq!(
+            content_result,
+            "<div><label htmlFor='name'>Name</label><output for='name'/></div>"
+        );
+    }
+
     #[test]
     fn update_with_remove_action_multi_line_and_react_component_content_wo_effect() {
         let content = "import styles from './styles.scss';\n\n function MyComponent {\n  return (\n    <div>      <span id='span_id'/>\n      <h1>Main title</h1>\n      <MyOtherComponent data-testid='other_test_id' />\n    </div>);}\n";
-        let content_result =
+        let (content_result, _) =
             apply_content_actions(content, &[Action::RemoveProp("data-testid".to_string())]);
         assert_eq!(content, content_result);
     }
+
+    #[test]
+    fn update_html_tagged_templates() {
+        let content = "const a = b < c;\nconst form = html`<FORM><LABEL for=name data-testid=label>Name</LABEL><INPUT id=name ?disabled=${off} data-testid=${id}></FORM>`;";
+        let (content_result, summary) = apply_content_actions(
+            content,
+            &[
+                Action::RemoveProp("disabled".to_string()),
+                Action::OnElement(
+                    "label".to_string(),
+                    Box::new(Action::RenameProp(
+                        "data-testid".to_string(),
+                        "data-qa".to_string(),
+                    )),
+                ),
+            ],
+        );
+        assert_eq!(
+            content_result,
+            "const a = b < c;\nconst form = html`<FORM><LABEL for=name data-qa=label>Name</LABEL><INPUT id=name  data-testid=${id}></FORM>`;"
+        );
+        assert_eq!(summary.components, 3);
+    }
+
+    #[test]
+    fn update_html_props_ignoring_their_case() {
+        let content = "<P DATA-TESTID=a>a</P><p Data-TestId=\"b\" ID=c>b</p>";
+        let actions = [
+            Action::RemoveProp("data-testid".to_string()),
+            Action::RenameProp("id".to_string(), "data-id".to_string()),
+        ];
+        let (content_result, _, _) =
+            apply_content_edits(content, Syntax::Html, &actions, Path::new("")).unwrap();
+        assert_eq!(content_result, "<P >a</P><p  data-id=c>b</p>");
+
+        // JSX props keep their case
+        let (content_result, _) = apply_content_actions("<p DATA-TESTID='a' />", &actions);
+        assert_eq!(content_result, "<p DATA-TESTID='a' />");
+    }
+
+    #[test]
+    fn run_changed_files_in_place() {
+        let folder = std::env::temp_dir().join("propsreplacer-watch-run");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("a.jsx"), "<p data-testid=\"a\" />").unwrap();
+        fs::write(folder.join("b.jsx"), "<p />").unwrap();
+
+        let config = ConfigBuilder::new()
+            .input(folder.clone())
+            .output(folder.clone())
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .build()
+            .unwrap();
+        let mut snapshot = watch::Snapshot::default();
+        let mut journal = Journal::default();
+
+        run_changed(&config, &mut snapshot, &mut journal).unwrap();
+        assert_eq!(fs::read_to_string(folder.join("a.jsx")).unwrap(), "<p  />");
+        // The files rewritten by the run are not changes
+        assert!(watch::Snapshot::take(&folder)
+            .unwrap()
+            .changed_since(&snapshot)
+            .is_empty());
+
+        fs::write(folder.join("b.jsx"), "<p data-testid=\"b\" />").unwrap();
+        run_changed(&config, &mut snapshot, &mut journal).unwrap();
+        assert_eq!(fs::read_to_string(folder.join("b.jsx")).unwrap(), "<p  />");
+
+        // The journal has the edits of both runs
+        let saved = Journal::load(config.journal_file.as_deref().unwrap()).unwrap();
+        let paths: Vec<_> = saved
+            .unwrap()
+            .files
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        assert_eq!(paths, vec![PathBuf::from("a.jsx"), PathBuf::from("b.jsx")]);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
 }
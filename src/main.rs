@@ -1,28 +1,46 @@
-// use std::fs;
 use std::{env, io::Error, process};
 
-use react_props_replacer::ConfigBuilder;
+use react_props_replacer::{cli, ConfigBuilder};
 
 fn main() -> Result<(), Error> {
     let args: Vec<String> = env::args().collect();
 
+    let args = cli::parse(&args[1..]).unwrap_or_else(|err| {
+        eprintln!(
+            "Problem parsing arguments: {}\nRun with `--help` for usage.",
+            err
+        );
+        process::exit(err.exit_code());
+    });
+
     // On empty args, print command help & exit
-    if args.len() == 1 {
-        println!("A CLI application to smart replace props in React files.\n\nUsage: react-props-replacer source_file [--output output_file] [--remove-prop [prop_name | \"data-testid\"]]");
+    if args.help || args == cli::Args::default() {
+        println!("{}", cli::usage());
+        return Ok(());
+    }
+
+    if args.version {
+        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
         return Ok(());
     }
 
     let config = ConfigBuilder::new()
-        .parse_args(&args[1..])
+        .args(args)
         .build()
         .unwrap_or_else(|err| {
             eprintln!("Problem parsing arguments: {}", err);
-            process::exit(1);
+            process::exit(err.exit_code());
         });
 
-    if let Err(e) = react_props_replacer::run(&config) {
+    let result = if config.watch {
+        react_props_replacer::watch(&config)
+    } else {
+        react_props_replacer::run(&config)
+    };
+
+    if let Err(e) = result {
         eprintln!("Application error: {}", e);
-        process::exit(1);
+        process::exit(e.exit_code());
     }
 
     Ok(())
@@ -0,0 +1,234 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{Action, Preset},
+    error::Error,
+    glob,
+    json::Value,
+};
+
+/// Config file names looked up, in order, from the input folder up to the root.
+pub const CONFIG_FILE_NAMES: [&str; 2] = ["propsreplacer.json", ".propsreplacer.json"];
+
+/// A rule of the config file: the actions to apply to the elements matching the selector,
+/// restricted to some files and/or to an environment.
+#[derive(Debug, PartialEq)]
+pub struct Rule {
+    pub selector: Option<String>,
+    pub actions: Vec<Action>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub env: Option<String>,
+}
+
+impl Rule {
+    /// Check if the rule applies to a file (path relative to the config file folder).
+    pub fn applies_to(&self, path: &str, env: Option<&str>) -> bool {
+        if self.env.is_some() && self.env.as_deref() != env {
+            return false;
+        }
+
+        (self.include.is_empty() || self.include.iter().any(|p| glob::matches(p, path)))
+            && !self.exclude.iter().any(|p| glob::matches(p, path))
+    }
+
+    fn from_json(value: &Value, path: &Path) -> Result<Self, Error> {
+        let invalid = |message: &str| Error::Config(format!("{}: {}", path.display(), message));
+
+        let selector = match value.get("selector").map(|s| s.as_str()) {
+            None | Some(Some("*")) => None,
+            Some(Some(selector)) => Some(selector.to_string()),
+            Some(None) => return Err(invalid("rule `selector` must be a string")),
+        };
+
+        let props = value.get("props").unwrap_or(&Value::Null);
+
+        let actions = match value.get("action").and_then(Value::as_str) {
+            None if value.get("preset").is_some() => value
+                .get("preset")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("rule `preset` must be a string"))
+                .and_then(|name| Preset::from_name(name).map_err(|err| invalid(&err.to_string())))?
+                .actions(),
+            None if value.get("script").is_some() => value
+                .get("script")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("rule `script` must be a path"))
+                .and_then(|script| script_actions(&path.with_file_name(script)))?,
+            Some("remove") => props
+                .as_array()
+                .and_then(|props| props.iter().map(Value::as_str).collect::<Option<Vec<_>>>())
+                .ok_or_else(|| invalid("`remove` rule `props` must be a list of names"))?
+                .into_iter()
+                .map(|name| Action::RemoveProp(name.to_string()))
+                .collect(),
+            Some("rename") => props
+                .as_object()
+                .and_then(|props| {
+                    props
+                        .iter()
+                        .map(|(old, new)| {
+                            new.as_str()
+                                .map(|new| Action::RenameProp(old.to_string(), new.to_string()))
+                        })
+                        .collect::<Option<Vec<_>>>()
+                })
+                .ok_or_else(|| invalid("`rename` rule `props` must be an object of names"))?,
+            Some(action) => return Err(invalid(&format!("unknown rule action `{}`", action))),
+            None => return Err(invalid("rule `action` is not present")),
+        };
+
+        Ok(Self {
+            actions: match &selector {
+                Some(selector) => actions
+                    .into_iter()
+                    .map(|action| Action::OnElement(selector.clone(), Box::new(action)))
+                    .collect(),
+                None => actions,
+            },
+            selector,
+            include: string_list(value.get("include"), || invalid("`include` must be a list"))?,
+            exclude: string_list(value.get("exclude"), || invalid("`exclude` must be a list"))?,
+            env: value.get("env").and_then(Value::as_str).map(str::to_string),
+        })
+    }
+}
+
+/// Rules loaded from a config file.
+#[derive(Debug, PartialEq)]
+pub struct RuleSet {
+    /// Folder of the config file, globs are relative to it.
+    pub root: PathBuf,
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Look for a config file walking up from the given path.
+    pub fn discover(from: &Path) -> Option<PathBuf> {
+        let from = fs::canonicalize(from).unwrap_or_else(|_| from.to_path_buf());
+
+        from.ancestors()
+            .flat_map(|folder| CONFIG_FILE_NAMES.iter().map(move |name| folder.join(name)))
+            .find(|file| file.is_file())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path).map_err(|err| Error::io(path, err))?;
+        let value = Value::parse(&content, path)?;
+
+        let rules = value
+            .get("rules")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                Error::Config(format!("{}: `rules` list is not present", path.display()))
+            })?
+            .iter()
+            .map(|rule| Rule::from_json(rule, path))
+            .collect::<Result<_, _>>()?;
+
+        let root = fs::canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        Ok(Self { root, rules })
+    }
+
+    /// Actions of the rules applying to a file.
+    pub fn actions_for(&self, file: &Path, env: Option<&str>) -> Vec<Action> {
+        let file = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+        let relative = file
+            .strip_prefix(&self.root)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        self.rules
+            .iter()
+            .filter(|rule| rule.applies_to(&relative, env))
+            .flat_map(|rule| rule.actions.iter().cloned())
+            .collect()
+    }
+}
+
+/// Action of a script rule.
+#[cfg(feature = "scripting")]
+fn script_actions(script: &Path) -> Result<Vec<Action>, Error> {
+    Ok(vec![Action::custom(crate::script::Script::load(script)?)])
+}
+
+#[cfg(not(feature = "scripting"))]
+fn script_actions(script: &Path) -> Result<Vec<Action>, Error> {
+    Err(Error::Config(format!(
+        "{}: script rules need a build with the `scripting` feature",
+        script.display()
+    )))
+}
+
+fn string_list(value: Option<&Value>, invalid: impl Fn() -> Error) -> Result<Vec<String>, Error> {
+    match value {
+        None => Ok(vec![]),
+        Some(value) => value
+            .as_array()
+            .and_then(|values| {
+                values
+                    .iter()
+                    .map(|v| v.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .ok_or_else(invalid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(json: &str) -> Result<Rule, Error> {
+        Rule::from_json(
+            &Value::parse(json, Path::new("propsreplacer.json")).unwrap(),
+            Path::new("propsreplacer.json"),
+        )
+    }
+
+    #[test]
+    fn rule_from_json() {
+        let rule = rule(
+            r#"{ "selector": "label", "action": "rename", "props": { "for": "htmlFor" },
+                 "include": ["src/**"], "exclude": ["*.test.jsx"], "env": "production" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rule.actions,
+            vec![Action::OnElement(
+                "label".to_string(),
+                Box::new(Action::RenameProp("for".to_string(), "htmlFor".to_string()))
+            )]
+        );
+        assert!(rule.applies_to("src/Form.jsx", Some("production")));
+        assert!(!rule.applies_to("src/Form.jsx", None));
+        assert!(!rule.applies_to("src/Form.test.jsx", Some("production")));
+        assert!(!rule.applies_to("lib/Form.jsx", Some("production")));
+    }
+
+    #[test]
+    fn preset_rule_from_json() {
+        let rule = rule(r#"{ "preset": "html-to-jsx", "env": "production" }"#).unwrap();
+        assert_eq!(rule.actions, Preset::HtmlToJsx.actions());
+    }
+
+    #[test]
+    fn invalid_rules_from_json() {
+        assert!(rule(r#"{ "preset": "unknown" }"#).is_err());
+        assert!(rule(r#"{ "action": "remove" }"#).is_err());
+        assert!(rule(r#"{ "action": "explode", "props": [] }"#).is_err());
+        assert!(rule(r#"{ "action": "remove", "props": { "a": "b" } }"#).is_err());
+        assert!(rule(r#"{ "action": "remove", "props": [], "include": "src" }"#).is_err());
+        assert!(rule(r#"{ "script": 42 }"#).is_err());
+    }
+}
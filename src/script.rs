@@ -0,0 +1,284 @@
+use std::{fs, path::Path};
+
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+
+use crate::{
+    cache,
+    component::{Component, ComponentType, Prop},
+    edit::Edits,
+    element_transform::ElementTransform,
+    error::Error,
+};
+
+/// Operations a script may run on an element before it fails, so an endless loop fails the file
+/// instead of hanging the run.
+const MAX_OPERATIONS: u64 = 1_000_000;
+/// Depth of nested function calls a script may reach.
+const MAX_CALL_LEVELS: usize = 64;
+
+/// Edit returned by a script, built with the `remove`, `rename`, `set` and `add` functions.
+#[derive(Debug, Clone)]
+enum ScriptEdit {
+    Remove(String),
+    Rename(String, String),
+    Set(String, String),
+    Add(String, Option<String>),
+}
+
+/// Rule written as a Rhai script, evaluated for every element.
+///
+/// The script sees the element as the `element` constant (`name`, `type` as `"html"` or
+/// `"react"`, and `props`, a map of prop names to raw values or `true` for booleans) and
+/// returns an edit, a list of edits or nothing:
+///
+/// ```rhai
+/// if element.name == "img" && !("alt" in element.props) {
+///     add("alt", "\"\"")
+/// }
+/// ```
+pub struct Script {
+    name: String,
+    /// Hash of the script source.
+    fingerprint: u64,
+    engine: Engine,
+    ast: AST,
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let source = fs::read_to_string(path).map_err(|err| Error::io(path, err))?;
+        Self::compile(&source, path)
+    }
+
+    /// Compile a script. `path` locates errors and names the rule in messages.
+    pub fn compile(source: &str, path: &Path) -> Result<Self, Error> {
+        let mut engine = Engine::new();
+
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .register_type_with_name::<ScriptEdit>("Edit")
+            .register_fn("remove", |prop: &str| ScriptEdit::Remove(prop.to_string()))
+            .register_fn("rename", |prop: &str, to: &str| {
+                ScriptEdit::Rename(prop.to_string(), to.to_string())
+            })
+            .register_fn("set", |prop: &str, value: &str| {
+                ScriptEdit::Set(prop.to_string(), value.to_string())
+            })
+            .register_fn("add", |prop: &str, value: &str| {
+                ScriptEdit::Add(prop.to_string(), Some(value.to_string()))
+            })
+            .register_fn("add", |prop: &str| ScriptEdit::Add(prop.to_string(), None));
+
+        let ast = engine.compile(source).map_err(|err| Error::Parse {
+            path: path.to_path_buf(),
+            line: err.1.line().unwrap_or(1),
+            column: err.1.position().unwrap_or(1),
+            message: err.0.to_string(),
+        })?;
+
+        Ok(Self {
+            name: path.display().to_string(),
+            fingerprint: cache::hash(source.as_bytes()),
+            engine,
+            ast,
+        })
+    }
+
+    fn edits(&self, component: &Component) -> Result<Vec<ScriptEdit>, String> {
+        let mut scope = Scope::new();
+        scope.push_constant("element", element_map(component));
+
+        let result = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast)
+            .map_err(|err| err.to_string())?;
+
+        if result.is_unit() {
+            Ok(vec![])
+        } else if result.is::<ScriptEdit>() {
+            Ok(vec![result.cast()])
+        } else if result.is::<Array>() {
+            result
+                .cast::<Array>()
+                .into_iter()
+                .map(|edit| {
+                    let type_name = edit.type_name();
+                    edit.try_cast::<ScriptEdit>()
+                        .ok_or_else(|| format!("expected a list of edits, found `{}`", type_name))
+                })
+                .collect()
+        } else {
+            Err(format!(
+                "expected an edit or a list of edits, found `{}`",
+                result.type_name()
+            ))
+        }
+    }
+}
+
+impl ElementTransform for Script {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn visit(&self, component: &Component, edits: &mut Edits) -> Result<(), String> {
+        for edit in self.edits(component)? {
+            match edit {
+                ScriptEdit::Remove(name) => named_props(component, &name).for_each(|prop| {
+                    edits.remove_prop(prop);
+                }),
+                ScriptEdit::Rename(name, to) => named_props(component, &name).for_each(|prop| {
+                    edits.rename_prop(prop, &to);
+                }),
+                ScriptEdit::Set(name, value) => named_props(component, &name).for_each(|prop| {
+                    edits.set_prop_value(prop, &value);
+                }),
+                ScriptEdit::Add(name, value) => {
+                    edits.add_prop(component, &name, value.as_deref());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+}
+
+fn named_props<'a, 's>(
+    component: &'a Component<'s>,
+    name: &'a str,
+) -> impl Iterator<Item = &'a Prop<'s>> {
+    component
+        .props()
+        .iter()
+        .filter(move |prop| !prop.is_spread() && prop.matches(name))
+}
+
+fn element_map(component: &Component) -> Map {
+    let props: Map = component
+        .props()
+        .iter()
+        .filter(|prop| !prop.is_spread())
+        .map(|prop| {
+            let value = match prop.raw_value() {
+                Some(raw) => Dynamic::from(raw.to_string()),
+                None => Dynamic::TRUE,
+            };
+            (prop.name.into(), value)
+        })
+        .collect();
+
+    let typo = match component.typo {
+        ComponentType::HtmlElement => "html",
+        ComponentType::ReactComponent => "react",
+        ComponentType::None => "",
+    };
+
+    let mut element = Map::new();
+    element.insert("name".into(), component.name.into());
+    element.insert("type".into(), typo.into());
+    element.insert("props".into(), props.into());
+    element
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{edit::apply_edits, syntax::Syntax};
+
+    fn run(script: &str, content: &str) -> Result<String, String> {
+        let script = Script::compile(script, Path::new("rule.rhai")).unwrap();
+        let component = Component::from(content);
+        let mut edits = Edits::new();
+
+        script.visit(&component, &mut edits)?;
+        Ok(apply_edits(content, edits.into_inner()).0)
+    }
+
+    #[test]
+    fn add_missing_alt() {
+        let script = r#"
+            if element.name == "img" && !("alt" in element.props) {
+                add("alt", "\"\"")
+            }
+        "#;
+
+        assert_eq!(
+            run(script, "<img src='a.png' />").unwrap(),
+            "<img alt=\"\" src='a.png' />"
+        );
+        assert_eq!(run(script, "<img alt='A' />").unwrap(), "<img alt='A' />");
+        assert_eq!(
+            run(script, "<Image src='a.png' />").unwrap(),
+            "<Image src='a.png' />"
+        );
+    }
+
+    #[test]
+    fn return_a_list_of_edits() {
+        let script = r#"
+            if element.type == "html" && element.props.disabled == true {
+                [rename("class", "className"), remove("disabled"), set("id", "{id}")]
+            }
+        "#;
+
+        assert_eq!(
+            run(script, "<button class='a' id='b' disabled>").unwrap(),
+            "<button className='a' id={id} >"
+        );
+    }
+
+    #[test]
+    fn edit_props_bound_by_directives() {
+        let script = Script::compile(
+            r#"[remove("data-testid"), rename("title", "aria-label")]"#,
+            Path::new("rule.rhai"),
+        )
+        .unwrap();
+        let content = "<button :data-testid=\"id\" v-bind:title=\"t\">";
+        let component = Component::parse_markup_at(content, 0, Syntax::Vue, false);
+        let mut edits = Edits::new();
+
+        script.visit(&component, &mut edits).unwrap();
+        assert_eq!(
+            apply_edits(content, edits.into_inner()).0,
+            "<button  v-bind:aria-label=\"t\">"
+        );
+    }
+
+    #[test]
+    fn invalid_scripts() {
+        let error = Script::compile("if (", Path::new("rule.rhai"))
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::Parse { line: 1, .. }));
+
+        assert_eq!(
+            run("42", "<img src='a.png' />").unwrap_err(),
+            "expected an edit or a list of edits, found `i64`"
+        );
+        assert!(run(r#"throw "no alt""#, "<img />")
+            .unwrap_err()
+            .contains("no alt"));
+        assert!(run("loop {}", "<img />").is_err());
+        assert!(run("fn f(n) { f(n + 1) } f(0)", "<img />").is_err());
+    }
+
+    #[test]
+    fn fingerprint_of_the_source() {
+        let compile = |source: &str| Script::compile(source, Path::new("rule.rhai")).unwrap();
+
+        assert_eq!(
+            compile("remove(\"id\")").fingerprint(),
+            compile("remove(\"id\")").fingerprint()
+        );
+        assert_ne!(
+            compile("remove(\"id\")").fingerprint(),
+            compile("remove(\"key\")").fingerprint()
+        );
+    }
+}
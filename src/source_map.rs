@@ -0,0 +1,321 @@
+use std::path::{Component as PathComponent, Path, PathBuf};
+
+use crate::{edit::Edit, json::Value};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Source map (v3) of a transformed content, mapping the output positions back to the input.
+///
+/// Columns count UTF-16 code units, as JavaScript tools do. Every line, every edit and every
+/// token of the output starts a segment, so stack trace columns map to the exact input column.
+pub fn source_map(input: &str, applied: &[Edit], file: &str, source: &str) -> Value {
+    let mut mappings = Mappings::default();
+    let mut last = 0;
+
+    for edit in applied {
+        mappings.copy(input, last, edit.span.start);
+        mappings.replace(input, edit.span.start, &edit.text);
+        last = edit.span.end;
+    }
+    mappings.copy(input, last, input.len());
+
+    Value::Object(vec![
+        ("version".to_string(), Value::Number(3.0)),
+        ("file".to_string(), Value::String(file.to_string())),
+        (
+            "sources".to_string(),
+            Value::Array(vec![Value::String(source.to_string())]),
+        ),
+        (
+            "sourcesContent".to_string(),
+            Value::Array(vec![Value::String(input.to_string())]),
+        ),
+        ("names".to_string(), Value::Array(vec![])),
+        ("mappings".to_string(), Value::String(mappings.encoded)),
+    ])
+}
+
+/// Whether an output file can link to a source map: JavaScript and TypeScript files. No tool
+/// reads a map linked from the markup files (HTML, Vue, Svelte, Astro).
+pub fn is_linkable(output: &Path) -> bool {
+    let extension = output.extension().and_then(|x| x.to_str());
+    matches!(extension, Some("jsx" | "tsx" | "js" | "ts"))
+}
+
+/// Comment linking an output file to its source map.
+pub fn url_comment(map: &Path) -> String {
+    let name = map.file_name().unwrap_or_default().to_string_lossy();
+    format!("//# sourceMappingURL={}\n", name)
+}
+
+/// Path of the source map of an output file (`App.jsx.map`).
+pub fn map_path(output: &Path) -> PathBuf {
+    let mut path = output.as_os_str().to_os_string();
+    path.push(".map");
+    PathBuf::from(path)
+}
+
+/// Path of `file` relative to the `folder`, `/` separated.
+pub fn relative_path(folder: &Path, file: &Path) -> String {
+    let absolute = |path: &Path| {
+        let path = match path.as_os_str().is_empty() {
+            true => Path::new("."),
+            false => path,
+        };
+        path.canonicalize()
+            .or_else(|_| std::env::current_dir().map(|current| current.join(path)))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    let folder = absolute(folder);
+    let file = absolute(file);
+
+    let common = folder
+        .components()
+        .zip(file.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let parents = folder.components().skip(common).map(|_| "..".to_string());
+    let rest = file
+        .components()
+        .skip(common)
+        .filter_map(|component| match component {
+            PathComponent::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        });
+
+    parents.chain(rest).collect::<Vec<_>>().join("/")
+}
+
+/// `mappings` field being encoded, with the state of the previous segment.
+#[derive(Default)]
+struct Mappings {
+    encoded: String,
+    /// Input offset reached, with its line and UTF-16 column: the input is scanned once, as
+    /// the ranges copied and replaced come in order.
+    source_offset: usize,
+    source_line: i64,
+    source_column: i64,
+    /// Output column of the current line, in UTF-16 code units.
+    column: i64,
+    previous_column: i64,
+    previous_source_line: i64,
+    previous_source_column: i64,
+    line_has_segment: bool,
+}
+
+impl Mappings {
+    /// Unchanged input range, copied to the output.
+    fn copy(&mut self, input: &str, start: usize, end: usize) {
+        self.advance(input, start);
+        let mut previous = None;
+
+        for c in input[start..end].chars() {
+            if previous.is_none_or(|previous| is_token_start(previous, c)) {
+                self.segment(self.source_line, self.source_column);
+            }
+
+            if c == '\n' {
+                self.new_line();
+                previous = None;
+            } else {
+                self.column += c.len_utf16() as i64;
+                previous = Some(c);
+            }
+            self.advance_char(c);
+        }
+        self.source_offset = end;
+    }
+
+    /// Text replacing the input from `start`, mapped to `start`.
+    fn replace(&mut self, input: &str, start: usize, text: &str) {
+        self.advance(input, start);
+
+        for (i, text_line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.new_line();
+            }
+            if !text_line.is_empty() {
+                self.segment(self.source_line, self.source_column);
+                self.column += text_line.encode_utf16().count() as i64;
+            }
+        }
+    }
+
+    /// Move the input position forward to an offset (after the ranges replaced).
+    fn advance(&mut self, input: &str, offset: usize) {
+        for c in input[self.source_offset..offset].chars() {
+            self.advance_char(c);
+        }
+        self.source_offset = offset;
+    }
+
+    fn advance_char(&mut self, c: char) {
+        if c == '\n' {
+            self.source_line += 1;
+            self.source_column = 0;
+        } else {
+            self.source_column += c.len_utf16() as i64;
+        }
+    }
+
+    fn segment(&mut self, source_line: i64, source_column: i64) {
+        if self.line_has_segment {
+            self.encoded.push(',');
+        }
+
+        vlq(&mut self.encoded, self.column - self.previous_column);
+        vlq(&mut self.encoded, 0);
+        vlq(&mut self.encoded, source_line - self.previous_source_line);
+        vlq(
+            &mut self.encoded,
+            source_column - self.previous_source_column,
+        );
+
+        self.previous_column = self.column;
+        self.previous_source_line = source_line;
+        self.previous_source_column = source_column;
+        self.line_has_segment = true;
+    }
+
+    fn new_line(&mut self) {
+        self.encoded.push(';');
+        self.column = 0;
+        self.previous_column = 0;
+        self.line_has_segment = false;
+    }
+}
+
+fn is_token_start(previous: char, c: char) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    !c.is_whitespace() && (!is_word(c) || !is_word(previous))
+}
+
+/// Base64 VLQ encoding of a value.
+fn vlq(encoded: &mut String, value: i64) {
+    let mut rest = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+
+    loop {
+        let mut digit = rest & 0b11111;
+        rest >>= 5;
+        if rest > 0 {
+            digit |= 0b100000;
+        }
+        encoded.push(BASE64[digit as usize] as char);
+
+        if rest == 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::Span;
+
+    #[test]
+    fn encode_vlq_values() {
+        let mut encoded = String::new();
+        for value in [0, 1, -1, 15, 16, -17, 1000] {
+            vlq(&mut encoded, value);
+            encoded.push(' ');
+        }
+
+        assert_eq!(encoded, "A C D e gB jB w+B ");
+    }
+
+    /// Decoded segments: output line and column, input line and column.
+    fn decode(mappings: &str) -> Vec<[i64; 4]> {
+        let mut segments = vec![];
+        let mut state = [0i64; 4];
+
+        for (line, encoded_line) in mappings.split(';').enumerate() {
+            state[0] = 0;
+            for segment in encoded_line.split(',').filter(|s| !s.is_empty()) {
+                let (mut values, mut value, mut shift) = (vec![], 0i64, 0);
+                for c in segment.bytes() {
+                    let digit = BASE64.iter().position(|&b| b == c).unwrap() as i64;
+                    value += (digit & 0b11111) << shift;
+                    shift += 5;
+                    if digit & 0b100000 == 0 {
+                        values.push(if value & 1 == 1 {
+                            -(value >> 1)
+                        } else {
+                            value >> 1
+                        });
+                        (value, shift) = (0, 0);
+                    }
+                }
+                state[0] += values[0];
+                state[2] += values[2];
+                state[3] += values[3];
+                segments.push([line as i64, state[0], state[2], state[3]]);
+            }
+        }
+
+        segments
+    }
+
+    #[test]
+    fn map_output_positions_to_the_input() {
+        let input = "<p id=\"a\" data-testid=\"b\">\n  {x}\n</p>";
+        let applied = vec![Edit::new(Span::new(10, 25), "")];
+        let map = source_map(input, &applied, "P.jsx", "../src/P.jsx");
+
+        assert_eq!(map.get("file").and_then(Value::as_str), Some("P.jsx"));
+        let segments = decode(map.get("mappings").and_then(Value::as_str).unwrap());
+        let input_position = |line, column| {
+            segments
+                .iter()
+                .find(|segment| segment[..2] == [line, column])
+                .map(|segment| [segment[2], segment[3]])
+        };
+
+        // `<p id="a" >`: `id` and `>` keep their columns, the next lines are unchanged
+        assert_eq!(input_position(0, 3), Some([0, 3]));
+        assert_eq!(input_position(0, 10), Some([0, 25]));
+        assert_eq!(input_position(1, 2), Some([1, 2]));
+        assert_eq!(input_position(2, 0), Some([2, 0]));
+    }
+
+    #[test]
+    fn map_positions_after_several_edits() {
+        let input = "<p\n  data-testid=\"é\"\n  id=\"a\" title=\"€\" for=\"b\" />";
+        let start = input.find("data-testid").unwrap();
+        let end = input.find("id=\"a\"").unwrap();
+        let for_start = input.find("for").unwrap();
+        let applied = vec![
+            Edit::new(Span::new(start - 3, end), " "),
+            Edit::new(Span::new(for_start, for_start + 3), "htmlFor"),
+        ];
+        let map = source_map(input, &applied, "P.jsx", "P.jsx");
+        let segments = decode(map.get("mappings").and_then(Value::as_str).unwrap());
+        let input_position = |line, column| {
+            segments
+                .iter()
+                .find(|segment| segment[..2] == [line, column])
+                .map(|segment| [segment[2], segment[3]])
+        };
+
+        // `<p id="a" title="€" htmlFor="b" />`: `€` is one UTF-16 unit
+        assert_eq!(input_position(0, 3), Some([2, 2]));
+        assert_eq!(input_position(0, 18), Some([2, 17]));
+        assert_eq!(input_position(0, 20), Some([2, 19]));
+        assert_eq!(input_position(0, 27), Some([2, 22]));
+        assert_eq!(input_position(0, 32), Some([2, 27]));
+    }
+
+    #[test]
+    fn relative_paths() {
+        assert_eq!(
+            relative_path(Path::new("dist/src"), Path::new("src/P.jsx")),
+            "../../src/P.jsx"
+        );
+        assert_eq!(relative_path(Path::new(""), Path::new("P.jsx")), "P.jsx");
+    }
+}
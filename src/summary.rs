@@ -0,0 +1,539 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    document::{location, Location},
+    edit::Change,
+    error::Error,
+    json::Value,
+};
+
+/// Output format of the run summary.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum SummaryFormat {
+    #[default]
+    Text,
+    Json,
+    /// SARIF 2.1.0 log, for code scanning annotations.
+    Sarif,
+    /// JUnit XML, one test case per file.
+    Junit,
+}
+
+impl FromStr for SummaryFormat {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            "junit" => Ok(Self::Junit),
+            _ => Err(Error::Config(format!(
+                "Unknown summary format `{}`, expected `text`, `json`, `sarif` or `junit`.",
+                name
+            ))),
+        }
+    }
+}
+
+/// Figures collected while applying the actions to a content.
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub components: usize,
+    /// Changes of the edits applied, in source order.
+    pub changes: Vec<Change>,
+}
+
+/// Summary of a processed file, with the location of the start and the end of the changes.
+#[derive(Debug)]
+pub struct FileSummary {
+    path: PathBuf,
+    components: usize,
+    changes: Vec<(Change, Location, Location)>,
+}
+
+impl FileSummary {
+    /// `content` locates the changes.
+    pub fn new(path: &Path, content: &str, summary: Summary) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            components: summary.components,
+            changes: summary
+                .changes
+                .into_iter()
+                .map(|change| {
+                    let start = location(content, change.span.start);
+                    let end = location(content, change.span.end);
+                    (change, start, end)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Summary of a run: the files processed, skipped and failed.
+#[derive(Debug, Default)]
+pub struct RunSummary {
+    files: Vec<FileSummary>,
+    skipped: Vec<(PathBuf, String)>,
+    errors: Vec<(PathBuf, String)>,
+}
+
+impl RunSummary {
+    pub fn add_file(&mut self, file: FileSummary) {
+        self.files.push(file);
+    }
+
+    pub fn add_skipped(&mut self, path: &Path, reason: &str) {
+        self.skipped.push((path.to_path_buf(), reason.to_string()));
+    }
+
+    pub fn add_error(&mut self, path: &Path, error: &Error) {
+        self.errors.push((path.to_path_buf(), error.to_string()));
+    }
+
+    pub fn render(&self, format: SummaryFormat) -> String {
+        match format {
+            SummaryFormat::Text => self.text(),
+            SummaryFormat::Json => self.json().to_string(),
+            SummaryFormat::Sarif => self.sarif().to_string(),
+            SummaryFormat::Junit => self.junit(),
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut text = String::from("\n------------------\nSUMMARY: ");
+
+        if self.files.len() != 1 {
+            text.push_str(&format!(
+                "\n\t* Number of files processed: {}",
+                self.files.len()
+            ));
+        }
+
+        text.push_str(&format!(
+            "\n\t* Number of components found: {}\n\t* Number of props changed: {}",
+            self.files.iter().map(|file| file.components).sum::<usize>(),
+            self.files
+                .iter()
+                .map(|file| file.changes.len())
+                .sum::<usize>()
+        ));
+
+        if !self.skipped.is_empty() {
+            text.push_str(&format!(
+                "\n\t* Number of files skipped: {}",
+                self.skipped.len()
+            ));
+        }
+
+        if !self.errors.is_empty() {
+            text.push_str(&format!("\n\t* Number of errors: {}", self.errors.len()));
+        }
+
+        text
+    }
+
+    fn json(&self) -> Value {
+        let string = |value: &str| Value::String(value.to_string());
+        let optional = |value: &Option<String>| value.as_deref().map_or(Value::Null, string);
+        let path = |path: &Path| string(&path.display().to_string());
+
+        let files = self
+            .files
+            .iter()
+            .map(|file| {
+                let changes = file
+                    .changes
+                    .iter()
+                    .map(|(change, location, _)| {
+                        Value::Object(vec![
+                            ("action".to_string(), string(change.kind.name())),
+                            ("rule".to_string(), string(&change.rule)),
+                            ("tag".to_string(), string(&change.tag)),
+                            ("prop".to_string(), string(&change.prop)),
+                            ("old_value".to_string(), optional(&change.old_value)),
+                            ("new_value".to_string(), optional(&change.new_value)),
+                            ("line".to_string(), Value::Number(location.line as f64)),
+                            ("column".to_string(), Value::Number(location.column as f64)),
+                        ])
+                    })
+                    .collect();
+
+                Value::Object(vec![
+                    ("path".to_string(), path(&file.path)),
+                    (
+                        "components".to_string(),
+                        Value::Number(file.components as f64),
+                    ),
+                    ("changes".to_string(), Value::Array(changes)),
+                ])
+            })
+            .collect();
+
+        let failures = |failures: &[(PathBuf, String)], key: &str| {
+            failures
+                .iter()
+                .map(|(file, message)| {
+                    Value::Object(vec![
+                        ("path".to_string(), path(file)),
+                        (key.to_string(), string(message)),
+                    ])
+                })
+                .collect()
+        };
+
+        Value::Object(vec![
+            ("files".to_string(), Value::Array(files)),
+            (
+                "skipped".to_string(),
+                Value::Array(failures(&self.skipped, "reason")),
+            ),
+            (
+                "errors".to_string(),
+                Value::Array(failures(&self.errors, "message")),
+            ),
+        ])
+    }
+
+    fn sarif(&self) -> Value {
+        let string = |value: &str| Value::String(value.to_string());
+        let number = |value: usize| Value::Number(value as f64);
+        let object = |entries: Vec<(&str, Value)>| {
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            )
+        };
+        let artifact = |path: &Path| object(vec![("uri", string(&uri(path)))]);
+
+        let mut rules: Vec<&str> = vec![];
+        let mut results = vec![];
+
+        for file in &self.files {
+            for (change, start, end) in &file.changes {
+                if !rules.contains(&change.rule.as_str()) {
+                    rules.push(&change.rule);
+                }
+
+                let region = object(vec![
+                    ("startLine", number(start.line)),
+                    ("startColumn", number(start.column)),
+                    ("endLine", number(end.line)),
+                    ("endColumn", number(end.column)),
+                ]);
+
+                results.push(object(vec![
+                    ("ruleId", string(&change.rule)),
+                    ("level", string("error")),
+                    ("message", object(vec![("text", string(&change.message()))])),
+                    (
+                        "locations",
+                        Value::Array(vec![object(vec![(
+                            "physicalLocation",
+                            object(vec![
+                                ("artifactLocation", artifact(&file.path)),
+                                ("region", region),
+                            ]),
+                        )])]),
+                    ),
+                ]));
+            }
+        }
+
+        let notifications = self
+            .errors
+            .iter()
+            .map(|(file, message)| {
+                object(vec![
+                    ("level", string("error")),
+                    ("message", object(vec![("text", string(message))])),
+                    (
+                        "locations",
+                        Value::Array(vec![object(vec![(
+                            "physicalLocation",
+                            object(vec![("artifactLocation", artifact(file))]),
+                        )])]),
+                    ),
+                ])
+            })
+            .collect();
+
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                object(vec![
+                    ("id", string(rule)),
+                    ("shortDescription", object(vec![("text", string(rule))])),
+                ])
+            })
+            .collect();
+
+        let driver = object(vec![
+            ("name", string(env!("CARGO_PKG_NAME"))),
+            ("version", string(env!("CARGO_PKG_VERSION"))),
+            ("rules", Value::Array(rules)),
+        ]);
+
+        let invocation = object(vec![
+            ("executionSuccessful", Value::Bool(self.errors.is_empty())),
+            ("toolExecutionNotifications", Value::Array(notifications)),
+        ]);
+
+        object(vec![
+            ("version", string("2.1.0")),
+            (
+                "$schema",
+                string("https://json.schemastore.org/sarif-2.1.0.json"),
+            ),
+            (
+                "runs",
+                Value::Array(vec![object(vec![
+                    ("tool", object(vec![("driver", driver)])),
+                    ("columnKind", string("unicodeCodePoints")),
+                    ("results", Value::Array(results)),
+                    ("invocations", Value::Array(vec![invocation])),
+                ])]),
+            ),
+        ])
+    }
+
+    fn junit(&self) -> String {
+        let name = env!("CARGO_PKG_NAME");
+        let tests = self.files.len() + self.errors.len();
+        let failures = self.files.iter().filter(|f| !f.changes.is_empty()).count();
+        let counts = format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"{}\"",
+            tests,
+            failures,
+            self.errors.len()
+        );
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!("<testsuites name=\"{}\" {}>\n", name, counts));
+        xml.push_str(&format!("  <testsuite name=\"{}\" {}>\n", name, counts));
+
+        for file in &self.files {
+            let path = xml_escape(&file.path.display().to_string());
+
+            if file.changes.is_empty() {
+                xml.push_str(&format!(
+                    "    <testcase classname=\"{}\" name=\"{}\"/>\n",
+                    path, path
+                ));
+                continue;
+            }
+
+            let findings: String = file
+                .changes
+                .iter()
+                .map(|(change, start, _)| {
+                    format!(
+                        "{}:{}:{}: [{}] {}\n",
+                        file.path.display(),
+                        start.line,
+                        start.column,
+                        change.rule,
+                        change.message()
+                    )
+                })
+                .collect();
+
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\">\n",
+                path, path
+            ));
+            xml.push_str(&format!(
+                "      <failure type=\"props\" message=\"props to change: {}\">{}</failure>\n",
+                file.changes.len(),
+                xml_escape(&findings)
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+
+        for (file, message) in &self.errors {
+            let path = xml_escape(&file.display().to_string());
+
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\">\n",
+                path, path
+            ));
+            xml.push_str(&format!(
+                "      <error message=\"{}\"/>\n",
+                xml_escape(message)
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n</testsuites>");
+        xml
+    }
+}
+
+/// URI of a file, with `/` separators: relative for relative paths, a `file://` URI for
+/// absolute ones. Characters other than the unreserved ones, `/` and `:` are percent-encoded.
+fn uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let has_drive =
+        matches!(path.as_bytes(), [letter, b':', b'/', ..] if letter.is_ascii_alphabetic());
+    let mut uri = String::with_capacity(path.len());
+
+    if path.starts_with('/') {
+        uri.push_str("file://");
+    } else if has_drive {
+        uri.push_str("file:///");
+    }
+    for c in path.trim_start_matches("./").bytes() {
+        match c {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(c as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", c)),
+        }
+    }
+
+    uri
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{component::Span, edit::ChangeKind};
+
+    fn summary() -> RunSummary {
+        let mut summary = RunSummary::default();
+        summary.add_file(FileSummary::new(
+            Path::new("./src/a.jsx"),
+            "<div>\n  <p data-testid=\"p\">",
+            Summary {
+                components: 2,
+                changes: vec![Change {
+                    kind: ChangeKind::Removed,
+                    rule: "remove-prop/data-testid".to_string(),
+                    tag: "p".to_string(),
+                    prop: "data-testid".to_string(),
+                    old_value: Some("\"p\"".to_string()),
+                    new_value: None,
+                    span: Span::new(11, 26),
+                }],
+            },
+        ));
+        summary.add_file(FileSummary::new(
+            Path::new("./src/b.jsx"),
+            "<br/>",
+            Summary::default(),
+        ));
+        summary.add_error(Path::new("c.jsx"), &Error::Config("invalid".to_string()));
+        summary
+    }
+
+    #[test]
+    fn render_json_with_changes_and_errors() {
+        let summary = summary();
+
+        let json = Value::parse(&summary.render(SummaryFormat::Json), Path::new("")).unwrap();
+        let file = &json.get("files").and_then(Value::as_array).unwrap()[0];
+        let change = &file.get("changes").and_then(Value::as_array).unwrap()[0];
+
+        assert_eq!(file.get("components"), Some(&Value::Number(2.0)));
+        assert_eq!(
+            change.get("action").and_then(Value::as_str),
+            Some("removed")
+        );
+        assert_eq!(
+            change.get("old_value").and_then(Value::as_str),
+            Some("\"p\"")
+        );
+        assert_eq!(change.get("new_value"), Some(&Value::Null));
+        assert_eq!(change.get("line"), Some(&Value::Number(2.0)));
+        assert_eq!(change.get("column"), Some(&Value::Number(6.0)));
+        assert_eq!(
+            json.get("errors").and_then(Value::as_array).unwrap()[0].get("message"),
+            Some(&Value::String("invalid".to_string()))
+        );
+    }
+
+    #[test]
+    fn render_sarif_results() {
+        let sarif = Value::parse(&summary().render(SummaryFormat::Sarif), Path::new("")).unwrap();
+        let run = &sarif.get("runs").and_then(Value::as_array).unwrap()[0];
+        let result = &run.get("results").and_then(Value::as_array).unwrap()[0];
+        let location = &result.get("locations").and_then(Value::as_array).unwrap()[0];
+        let physical = location.get("physicalLocation").unwrap();
+        let region = physical.get("region").unwrap();
+
+        assert_eq!(sarif.get("version").and_then(Value::as_str), Some("2.1.0"));
+        assert_eq!(
+            result.get("ruleId").and_then(Value::as_str),
+            Some("remove-prop/data-testid")
+        );
+        assert_eq!(
+            physical.get("artifactLocation").and_then(|a| a.get("uri")),
+            Some(&Value::String("src/a.jsx".to_string()))
+        );
+        assert_eq!(region.get("startColumn"), Some(&Value::Number(6.0)));
+        assert_eq!(region.get("endColumn"), Some(&Value::Number(21.0)));
+    }
+
+    #[test]
+    fn uri_of_relative_and_absolute_paths() {
+        assert_eq!(uri(Path::new("./src/a.jsx")), "src/a.jsx");
+        assert_eq!(uri(Path::new("src\\my app\\a.jsx")), "src/my%20app/a.jsx");
+        assert_eq!(
+            uri(Path::new("/home/me/my app/é.jsx")),
+            "file:///home/me/my%20app/%C3%A9.jsx"
+        );
+        assert_eq!(
+            uri(Path::new("C:\\Users\\me\\a.jsx")),
+            "file:///C:/Users/me/a.jsx"
+        );
+    }
+
+    #[test]
+    fn render_junit_test_cases() {
+        assert_eq!(
+            summary().render(SummaryFormat::Junit),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuites name=\"react-props-replacer\" tests=\"3\" failures=\"1\" errors=\"1\">\n\
+             \x20 <testsuite name=\"react-props-replacer\" tests=\"3\" failures=\"1\" errors=\"1\">\n\
+             \x20   <testcase classname=\"./src/a.jsx\" name=\"./src/a.jsx\">\n\
+             \x20     <failure type=\"props\" message=\"props to change: 1\">\
+             ./src/a.jsx:2:6: [remove-prop/data-testid] Prop `data-testid` of `&lt;p&gt;` must be removed\n\
+             </failure>\n\
+             \x20   </testcase>\n\
+             \x20   <testcase classname=\"./src/b.jsx\" name=\"./src/b.jsx\"/>\n\
+             \x20   <testcase classname=\"c.jsx\" name=\"c.jsx\">\n\
+             \x20     <error message=\"invalid\"/>\n\
+             \x20   </testcase>\n\
+             \x20 </testsuite>\n\
+             </testsuites>"
+        );
+    }
+
+    #[test]
+    fn render_text() {
+        let mut summary = RunSummary::default();
+        summary.add_file(FileSummary::new(Path::new("a.jsx"), "", Summary::default()));
+
+        assert_eq!(
+            summary.render(SummaryFormat::Text),
+            "\n------------------\nSUMMARY: \n\t* Number of components found: 0\n\t* Number of props changed: 0"
+        );
+        assert_eq!(
+            self::summary().render(SummaryFormat::Text),
+            "\n------------------\nSUMMARY: \n\t* Number of files processed: 2\n\t* Number of components found: 2\n\t* Number of props changed: 1\n\t* Number of errors: 1"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use std::path::Path;
+
+use crate::component::{is_name_char, skip_expression, Component, Span};
+
+/// Language of a source file, telling which parts of its content hold elements.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Syntax {
+    /// JavaScript and TypeScript, with JSX elements anywhere in the code and HTML elements in
+    /// `html` tagged templates (lit).
+    #[default]
+    Jsx,
+    /// HTML document, `<script>` and `<style>` elements and comments excluded.
+    Html,
+    /// Vue single-file component: only the `<template>` block holds elements.
+    Vue,
+    /// Svelte component: the markup around the `<script>` and `<style>` elements.
+    Svelte,
+    /// Astro component: the markup after the frontmatter, `<script>` and `<style>` elements
+    /// excluded.
+    Astro,
+}
+
+/// Part of a content holding elements.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) struct Markup {
+    pub span: Span,
+    /// Elements parsed with the HTML rules (see `Component::parse_markup_at`).
+    pub html: bool,
+}
+
+impl Syntax {
+    /// Syntax of a file from its extension, JSX by default.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("html" | "htm") => Self::Html,
+            Some("vue") => Self::Vue,
+            Some("svelte") => Self::Svelte,
+            Some("astro") => Self::Astro,
+            _ => Self::Jsx,
+        }
+    }
+
+    /// Parts of the content where elements are looked up, in source order.
+    pub(crate) fn markup(&self, source: &str) -> Vec<Markup> {
+        let spans = match self {
+            Self::Jsx => return jsx_markup(source),
+            Self::Html | Self::Svelte => without_scripts(source, 0),
+            Self::Vue => vue_templates(source),
+            Self::Astro => without_scripts(source, astro_frontmatter_end(source)),
+        };
+        let html = *self == Self::Html;
+
+        spans
+            .into_iter()
+            .map(|span| Markup { span, html })
+            .collect()
+    }
+}
+
+/// JSX code around its `html` tagged templates, and the content of the templates as HTML.
+/// Templates nested in the `${}` expressions of a template are part of it.
+fn jsx_markup(source: &str) -> Vec<Markup> {
+    let mut markup = vec![];
+    let mut jsx_start = 0;
+
+    for (i, _) in source.match_indices("html`") {
+        let is_tag = source[..i]
+            .bytes()
+            .next_back()
+            .is_none_or(|c| !c.is_ascii_alphanumeric() && c != b'_' && c != b'$');
+        if i < jsx_start || !is_tag {
+            continue;
+        }
+
+        let start = i + "html`".len();
+        let end = template_end(source.as_bytes(), start);
+        markup.push(Markup {
+            span: Span::new(jsx_start, i),
+            html: false,
+        });
+        markup.push(Markup {
+            span: Span::new(start, end),
+            html: true,
+        });
+        jsx_start = end;
+    }
+
+    markup.push(Markup {
+        span: Span::new(jsx_start, source.len()),
+        html: false,
+    });
+    markup.retain(|markup| !markup.span.is_empty());
+    markup
+}
+
+/// Offset of the backtick closing a template literal whose content starts at `from`, or the
+/// content end.
+fn template_end(bytes: &[u8], from: usize) -> usize {
+    let mut i = from;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'`' => return i,
+            b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                i = skip_expression(bytes, i + 1);
+                continue;
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+/// Content of the top-level `<template>` blocks of a Vue single-file component, the root tag
+/// excluded. `<script>`, `<style>`, custom blocks and templates in another language
+/// (`lang="pug"`) are skipped.
+fn vue_templates(source: &str) -> Vec<Span> {
+    let mut templates = vec![];
+    let mut i = 0;
+
+    while let Some(offset) = source[i..].find('<') {
+        let start = i + offset;
+        if source[start..].starts_with("<!--") {
+            i = source[start..]
+                .find("-->")
+                .map_or(source.len(), |end| start + end + 3);
+            continue;
+        }
+
+        let block = Component::parse_at(source, start);
+        if block.name.is_empty() || block.self_closing {
+            i = block.span.end.max(start + 1);
+            continue;
+        }
+
+        let end = closing_tag(source, block.name, block.span.end);
+        let lang = block.prop("lang").and_then(|prop| prop.literal_value());
+        if block.name == "template" && lang.is_none_or(|lang| lang == "html") {
+            templates.push(Span::new(block.span.end, end));
+        }
+
+        i = end;
+    }
+
+    templates
+}
+
+/// Spans of the content from `start`, around its `<script>` and `<style>` elements and its
+/// comments.
+fn without_scripts(source: &str, start: usize) -> Vec<Span> {
+    let mut markup = vec![];
+    let mut markup_start = start;
+    let mut i = start;
+
+    while let Some(offset) = source[i..].find('<') {
+        let tag_start = i + offset;
+        if source[tag_start..].starts_with("<!--") {
+            let end = source[tag_start..]
+                .find("-->")
+                .map_or(source.len(), |end| tag_start + end + 3);
+            markup.push(Span::new(markup_start, tag_start));
+            markup_start = end;
+            i = end;
+            continue;
+        }
+
+        let name_end = source[tag_start + 1..]
+            .bytes()
+            .position(|c| !is_name_char(c))
+            .map_or(source.len(), |end| tag_start + 1 + end);
+        let name = &source[tag_start + 1..name_end];
+
+        if !name.eq_ignore_ascii_case("script") && !name.eq_ignore_ascii_case("style") {
+            i = tag_start + 1;
+            continue;
+        }
+
+        let tag = Component::parse_at(source, tag_start);
+        let end = match tag.self_closing {
+            true => tag.span.end,
+            false => {
+                let closing = closing_tag(source, name, tag.span.end);
+                source[closing..]
+                    .find('>')
+                    .map_or(source.len(), |end| closing + end + 1)
+            }
+        };
+
+        markup.push(Span::new(markup_start, tag_start));
+        markup_start = end;
+        i = end;
+    }
+
+    markup.push(Span::new(markup_start, source.len()));
+    markup.retain(|span| !span.is_empty());
+    markup
+}
+
+/// Offset after the `---` fences of the frontmatter of an Astro component, 0 without one.
+fn astro_frontmatter_end(source: &str) -> usize {
+    let content = source.trim_start();
+    if !content.starts_with("---") {
+        return 0;
+    }
+
+    let open = source.len() - content.len() + 3;
+    source[open..]
+        .find("\n---")
+        .map_or(source.len(), |end| open + end + 4)
+}
+
+/// Offset of the `</name>` tag closing an element whose content starts at `from`, elements of
+/// the same name nested in it included. Unclosed elements end with the content.
+fn closing_tag(source: &str, name: &str, from: usize) -> usize {
+    let is_tag_end = |offset: usize| {
+        source[offset..]
+            .bytes()
+            .next()
+            .is_none_or(|c| c.is_ascii_whitespace() || c == b'>' || c == b'/')
+    };
+    let starts_with_name = |tag: &str| {
+        tag.get(..name.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(name))
+    };
+    let mut depth = 1;
+
+    for (i, _) in source[from..].match_indices('<') {
+        let tag = &source[from + i + 1..];
+        if let Some(closing) = tag.strip_prefix('/') {
+            if starts_with_name(closing) && is_tag_end(from + i + 2 + name.len()) {
+                depth -= 1;
+                if depth == 0 {
+                    return from + i;
+                }
+            }
+        } else if starts_with_name(tag)
+            && is_tag_end(from + i + 1 + name.len())
+            && !Component::parse_at(source, from + i).self_closing
+        {
+            depth += 1;
+        }
+    }
+
+    source.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syntax_of_files() {
+        assert_eq!(
+            Syntax::from_path(Path::new("public/index.html")),
+            Syntax::Html
+        );
+        assert_eq!(Syntax::from_path(Path::new("src/App.vue")), Syntax::Vue);
+        assert_eq!(
+            Syntax::from_path(Path::new("src/Card.svelte")),
+            Syntax::Svelte
+        );
+        assert_eq!(
+            Syntax::from_path(Path::new("src/index.astro")),
+            Syntax::Astro
+        );
+        assert_eq!(Syntax::from_path(Path::new("src/App.tsx")), Syntax::Jsx);
+        assert_eq!(Syntax::from_path(Path::new("-")), Syntax::Jsx);
+    }
+
+    #[test]
+    fn vue_template_blocks() {
+        let source = "<!-- <template>comment</template> -->\n<template>\n  <div>\n    <template v-if=\"a\"><b /></template>\n  </div>\n</template>\n<script setup lang=\"ts\">\nconst a = ref<boolean>(true);\n</script>\n<style>\ndiv > b {}\n</style>\n<template lang=\"pug\">div</template>";
+        let templates = Syntax::Vue.markup(source);
+
+        assert_eq!(
+            templates
+                .iter()
+                .map(|markup| &source[markup.span.start..markup.span.end])
+                .collect::<Vec<_>>(),
+            vec!["\n  <div>\n    <template v-if=\"a\"><b /></template>\n  </div>\n"]
+        );
+    }
+
+    #[test]
+    fn svelte_and_astro_markup() {
+        let markup = |syntax: Syntax, source: &'static str| {
+            syntax
+                .markup(source)
+                .iter()
+                .map(|markup| &source[markup.span.start..markup.span.end])
+                .collect::<Vec<_>>()
+        };
+
+        let source = "<script lang=\"ts\">\n  let items: Array<string> = [];\n</script>\n\n<ul>{#each items as item}<li>{item}</li>{/each}</ul>\n<style>\n  ul > li {}\n</style>\n";
+        assert_eq!(
+            markup(Syntax::Svelte, source),
+            vec![
+                "\n\n<ul>{#each items as item}<li>{item}</li>{/each}</ul>\n",
+                "\n"
+            ]
+        );
+
+        let source = "---\nconst items: Array<string> = [];\n---\n<ul>{items.map((item) => <li>{item}</li>)}</ul>\n<script>\n  document.querySelector<HTMLElement>('ul');\n</script>";
+        assert_eq!(
+            markup(Syntax::Astro, source),
+            vec!["\n<ul>{items.map((item) => <li>{item}</li>)}</ul>\n"]
+        );
+    }
+
+    #[test]
+    fn html_markup_and_tagged_templates() {
+        let markup = |syntax: Syntax, source: &'static str| {
+            syntax
+                .markup(source)
+                .iter()
+                .map(|markup| (&source[markup.span.start..markup.span.end], markup.html))
+                .collect::<Vec<_>>()
+        };
+
+        let source = "<p>a</p><!-- <b> --><SCRIPT>if (a<b) {}</SCRIPT><br>";
+        assert_eq!(
+            markup(Syntax::Html, source),
+            vec![("<p>a</p>", true), ("<br>", true)]
+        );
+
+        let source = "const a = html`<ul>${items.map((i) => html`<li>${i}</li>`)}</ul>`;\nconst b = <div />;\nconst c = xhtml`<p>`;";
+        assert_eq!(
+            markup(Syntax::Jsx, source),
+            vec![
+                ("const a = ", false),
+                ("<ul>${items.map((i) => html`<li>${i}</li>`)}</ul>", true),
+                ("`;\nconst b = <div />;\nconst c = xhtml`<p>`;", false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use crate::component::{is_name_char, Component, PropValue, Span};
+use crate::document::Document;
+use crate::edit::{Change, ChangeKind};
+use crate::syntax::Syntax;
+
+/// Difference between a content and its transformed version not explained by the changes.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Mismatch {
+    /// Offset in the input content.
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Opening or closing tag, in source order.
+#[derive(Debug, PartialEq)]
+enum Tag<'a> {
+    Open { name: &'a str, self_closing: bool },
+    Close(&'a str),
+}
+
+/// Value of a prop as expected in the output.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Value<'a> {
+    Raw(Option<&'a str>),
+    /// Expression containing elements, which can be changed themselves.
+    Nested,
+}
+
+/// Parse the output again and check it has the same elements and nesting as the input, and
+/// the same props except the ones of the `changes` made by the actions.
+pub(crate) fn verify(
+    input: &str,
+    output: &str,
+    syntax: Syntax,
+    changes: &[Change],
+) -> Result<(), Mismatch> {
+    let input = Document::parse_as(input, syntax);
+    let output = Document::parse_as(output, syntax);
+
+    let input_tags = tags(&input);
+    let output_tags = tags(&output);
+    if let Some(i) = (0..input_tags.len().max(output_tags.len()))
+        .find(|&i| input_tags.get(i).map(|tag| &tag.1) != output_tags.get(i).map(|tag| &tag.1))
+    {
+        let offset = input_tags
+            .get(i)
+            .map_or(input.source().len(), |(offset, _)| *offset);
+        let message = match (input_tags.get(i), output_tags.get(i)) {
+            (Some((_, tag)), Some((_, other))) => {
+                format!("{} became {}", describe(tag), describe(other))
+            }
+            (Some((_, tag)), None) => format!("{} is missing", describe(tag)),
+            (None, Some((_, other))) => format!("unexpected {}", describe(other)),
+            (None, None) => unreachable!(),
+        };
+
+        return Err(Mismatch { offset, message });
+    }
+
+    let mut changes_by_span: HashMap<Span, Vec<&Change>> = HashMap::new();
+    for change in changes {
+        changes_by_span.entry(change.span).or_default().push(change);
+    }
+
+    for (component, updated) in input.components().iter().zip(output.components()) {
+        let expected = expected_props(component, input.components(), &changes_by_span);
+        let found = props(updated, output.components());
+
+        if expected != found {
+            return Err(Mismatch {
+                offset: component.span.start,
+                message: format!("props of `<{}>` changed unexpectedly", component.name),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Opening and closing tags of the document with their offsets. Closing tags in the literal
+/// values of props are ignored.
+fn tags<'a>(document: &Document<'a>) -> Vec<(usize, Tag<'a>)> {
+    let source = document.source();
+    let mut literals: Vec<Span> = document
+        .components()
+        .iter()
+        .flat_map(|component| component.props().iter())
+        .filter(|prop| prop.is_literal())
+        .map(|prop| prop.span)
+        .collect();
+    literals.sort();
+
+    let mut tags: Vec<(usize, Tag)> = document
+        .components()
+        .iter()
+        .map(|component| {
+            let tag = Tag::Open {
+                name: component.name,
+                self_closing: component.self_closing,
+            };
+            (component.span.start, tag)
+        })
+        .collect();
+
+    let mut literal = 0;
+    for (i, _) in source.match_indices("</") {
+        while literals.get(literal).is_some_and(|span| span.end <= i) {
+            literal += 1;
+        }
+        if literals.get(literal).is_some_and(|span| span.contains(i)) {
+            continue;
+        }
+
+        let name_end = source[i + 2..].find('>').map(|end| i + 2 + end);
+        if let Some(name) = name_end.map(|end| source[i + 2..end].trim()) {
+            if name.bytes().all(is_name_char) {
+                tags.push((i, Tag::Close(name)));
+            }
+        }
+    }
+
+    tags.sort_by_key(|(offset, _)| *offset);
+    tags
+}
+
+/// Props of the input element after the changes, sorted.
+fn expected_props<'a>(
+    component: &Component<'a>,
+    components: &[Component],
+    changes: &HashMap<Span, Vec<&'a Change>>,
+) -> Vec<(&'a str, Value<'a>)> {
+    let mut props = vec![];
+
+    for prop in component.props() {
+        let mut name = prop.name;
+        let mut value = value(prop.span, prop.value, components);
+        let mut removed = false;
+
+        for change in changes.get(&prop.span).into_iter().flatten() {
+            match change.kind {
+                ChangeKind::Removed => removed = true,
+                ChangeKind::Renamed => name = change.new_value.as_deref().unwrap_or_default(),
+                ChangeKind::Updated => value = Value::Raw(change.new_value.as_deref()),
+                ChangeKind::Added => (),
+            }
+        }
+
+        if !removed {
+            props.push((name, value));
+        }
+    }
+
+    let added = changes.get(&component.name_span).into_iter().flatten();
+    for change in added.filter(|change| change.kind == ChangeKind::Added) {
+        props.push((
+            change.prop.as_str(),
+            Value::Raw(change.new_value.as_deref()),
+        ));
+    }
+
+    props.sort();
+    props
+}
+
+/// Props of the output element, sorted.
+fn props<'a>(component: &Component<'a>, components: &[Component]) -> Vec<(&'a str, Value<'a>)> {
+    let mut props: Vec<_> = component
+        .props()
+        .iter()
+        .map(|prop| (prop.name, value(prop.span, prop.value, components)))
+        .collect();
+
+    props.sort();
+    props
+}
+
+/// Value of a prop, `Nested` when one of the document `components` starts in it.
+fn value<'a>(span: Span, value: PropValue<'a>, components: &[Component]) -> Value<'a> {
+    let first_after = components.partition_point(|component| component.span.start < span.start);
+    let nested = components
+        .get(first_after)
+        .is_some_and(|component| span.contains(component.span.start));
+
+    match value {
+        _ if nested => Value::Nested,
+        PropValue::Boolean => Value::Raw(None),
+        PropValue::Literal(raw)
+        | PropValue::Expression(raw)
+        | PropValue::Unquoted(raw)
+        | PropValue::Spread(raw) => Value::Raw(Some(raw)),
+    }
+}
+
+fn describe(tag: &Tag) -> String {
+    match tag {
+        Tag::Open {
+            name,
+            self_closing: true,
+        } => format!("`<{} />`", name),
+        Tag::Open { name, .. } => format!("`<{}>`", name),
+        Tag::Close(name) => format!("`</{}>`", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::{apply_edits, Edits};
+
+    fn transformed(input: &str, edit: impl Fn(&Component, &mut Edits)) -> (String, Vec<Change>) {
+        let document = Document::parse(input);
+        let mut edits = Edits::new();
+        for component in document.components() {
+            edit(component, &mut edits);
+        }
+
+        let (output, applied) = apply_edits(input, edits.into_inner());
+        (
+            output,
+            applied.into_iter().filter_map(|edit| edit.change).collect(),
+        )
+    }
+
+    #[test]
+    fn verify_the_changes_of_the_actions() {
+        let input = "<div id=\"a\" title=\"</b>\" icon={<Icon data-testid=\"x\" />}>\n  <label for=\"a\">A</label>\n</div>";
+        let (output, changes) = transformed(input, |component, edits| {
+            if let Some(prop) = component.prop("data-testid") {
+                edits.remove_prop(prop);
+            }
+            if let Some(prop) = component.prop("for") {
+                edits.rename_prop(prop, "htmlFor");
+            }
+            if component.name == "div" {
+                edits.add_prop(component, "role", Some("\"main\""));
+            }
+        });
+
+        assert_eq!(
+            output,
+            "<div role=\"main\" id=\"a\" title=\"</b>\" icon={<Icon  />}>\n  <label htmlFor=\"a\">A</label>\n</div>"
+        );
+        assert_eq!(verify(input, &output, Syntax::Jsx, &changes), Ok(()));
+    }
+
+    #[test]
+    fn refuse_broken_trees_and_unexpected_props() {
+        let input = "<div id=\"a\">\n  <span>text</span>\n</div>";
+
+        let output = "<div id=\"a\">\n  <span>text</div>";
+        assert_eq!(
+            verify(input, output, Syntax::Jsx, &[]),
+            Err(Mismatch {
+                offset: 25,
+                message: "`</span>` became `</div>`".to_string()
+            })
+        );
+
+        let output = "<div id=\"b\">\n  <span>text</span>\n</div>";
+        assert_eq!(
+            verify(input, output, Syntax::Jsx, &[]),
+            Err(Mismatch {
+                offset: 0,
+                message: "props of `<div>` changed unexpectedly".to_string()
+            })
+        );
+
+        let (output, changes) = transformed(input, |component, edits| {
+            if let Some(prop) = component.prop("id") {
+                edits.set_prop_value(prop, "\"x\" data-y");
+            }
+        });
+        assert_eq!(output, "<div id=\"x\" data-y>\n  <span>text</span>\n</div>");
+        assert!(verify(input, &output, Syntax::Jsx, &changes).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{error::Error, glob};
+
+/// Extensions of the files looked up in folders.
+pub const SOURCE_EXTENSIONS: [&str; 8] =
+    ["jsx", "tsx", "js", "ts", "html", "vue", "svelte", "astro"];
+
+/// Folders never walked.
+const SKIPPED_FOLDERS: [&str; 2] = ["node_modules", "target"];
+
+const GITIGNORE: &str = ".gitignore";
+
+/// Source files of a folder and its subfolders, sorted by path. Hidden entries and the ones
+/// ignored by the `.gitignore` files of the folder, its subfolders and its parent folders in
+/// the same git repository are skipped. A file path is returned as is.
+pub fn source_files(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = vec![];
+    walk(path, &mut parent_ignores(path), &mut files, &mut vec![])?;
+    files.sort();
+
+    Ok(files)
+}
+
+/// Folders walked to find the source files of a path, the path included, sorted by path. The
+/// folder of a file path is returned.
+pub fn source_folders(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    if !path.is_dir() {
+        return Ok(vec![path
+            .parent()
+            .filter(|folder| !folder.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf()]);
+    }
+
+    let mut folders = vec![path.to_path_buf()];
+    walk(path, &mut parent_ignores(path), &mut vec![], &mut folders)?;
+    folders.sort();
+
+    Ok(folders)
+}
+
+fn walk(
+    folder: &Path,
+    ignores: &mut Vec<Ignore>,
+    files: &mut Vec<PathBuf>,
+    folders: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    let entries = fs::read_dir(folder).map_err(|err| Error::io(folder, err))?;
+    let ignores_len = ignores.len();
+    ignores.extend(Ignore::read(folder.to_path_buf(), PathBuf::new()));
+
+    for entry in entries {
+        let path = entry.map_err(|err| Error::io(folder, err))?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let is_dir = path.is_dir();
+        if is_ignored(ignores, &path, is_dir) {
+            continue;
+        }
+
+        if is_dir {
+            if !SKIPPED_FOLDERS.contains(&name) {
+                walk(&path, ignores, files, folders)?;
+                folders.push(path);
+            }
+        } else if is_source_file(&path) {
+            files.push(path);
+        }
+    }
+
+    ignores.truncate(ignores_len);
+    Ok(())
+}
+
+/// Rules of a `.gitignore` file.
+struct Ignore {
+    /// Folder of the walk the rules apply to.
+    folder: PathBuf,
+    /// Path of `folder` relative to the folder of the `.gitignore` file, for the files of
+    /// parent folders.
+    prefix: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+struct IgnoreRule {
+    pattern: String,
+    negated: bool,
+    only_folders: bool,
+}
+
+impl Ignore {
+    fn read(folder: PathBuf, prefix: PathBuf) -> Option<Self> {
+        let content = fs::read_to_string(folder.join(GITIGNORE)).ok()?;
+        let rules = content.lines().filter_map(IgnoreRule::parse).collect();
+
+        Some(Self {
+            folder,
+            prefix,
+            rules,
+        })
+    }
+
+    /// Whether the last rule matching the path ignores it, `None` when no rule matches.
+    fn ignores(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = self.prefix.join(path.strip_prefix(&self.folder).ok()?);
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| (is_dir || !rule.only_folders) && glob::matches(&rule.pattern, &relative))
+            .map(|rule| !rule.negated)
+    }
+}
+
+impl IgnoreRule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line),
+        };
+        let (only_folders, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        // Patterns with a `/` are relative to the `.gitignore` folder, as glob patterns
+        let pattern = match pattern.strip_prefix('/') {
+            Some(pattern) => format!("./{}", pattern),
+            None => pattern.to_string(),
+        };
+
+        Some(Self {
+            pattern,
+            negated,
+            only_folders,
+        })
+    }
+}
+
+/// Rules of the `.gitignore` files of the parent folders of the walked folder, up to the root
+/// of its git repository. Outside of a repository, parent folders are not looked up.
+fn parent_ignores(folder: &Path) -> Vec<Ignore> {
+    let Ok(canonical) = fs::canonicalize(folder) else {
+        return vec![];
+    };
+    let Some(root) = canonical.ancestors().find(|dir| dir.join(".git").exists()) else {
+        return vec![];
+    };
+
+    let mut ignores: Vec<Ignore> = canonical
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(root))
+        .filter_map(|dir| {
+            let prefix = canonical.strip_prefix(dir).ok()?.to_path_buf();
+            let ignore = Ignore::read(dir.to_path_buf(), prefix)?;
+            Some(Ignore {
+                folder: folder.to_path_buf(),
+                ..ignore
+            })
+        })
+        .collect();
+
+    // From the repository root, so the rules of the closest files win
+    ignores.reverse();
+    ignores
+}
+
+fn is_ignored(ignores: &[Ignore], path: &Path, is_dir: bool) -> bool {
+    ignores
+        .iter()
+        .rev()
+        .find_map(|ignore| ignore.ignores(path, is_dir))
+        .unwrap_or(false)
+}
+
+fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .is_some_and(|x| SOURCE_EXTENSIONS.contains(&x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_files_of_a_folder() {
+        let files = source_files(Path::new("tests/assets/report")).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("tests/assets/report/Form.jsx"),
+                PathBuf::from("tests/assets/report/components/Button.tsx"),
+            ]
+        );
+
+        assert_eq!(
+            source_folders(Path::new("tests/assets/report")).unwrap(),
+            vec![
+                PathBuf::from("tests/assets/report"),
+                PathBuf::from("tests/assets/report/components"),
+            ]
+        );
+        assert_eq!(
+            source_folders(Path::new("tests/assets/report/Form.jsx")).unwrap(),
+            vec![PathBuf::from("tests/assets/report")]
+        );
+    }
+
+    #[test]
+    fn source_files_not_ignored_by_gitignore_files() {
+        let folder = std::env::temp_dir().join("propsreplacer-walk-gitignore");
+        let _ = fs::remove_dir_all(&folder);
+        for file in [
+            "App.jsx",
+            "build/App.jsx",
+            "src/App.jsx",
+            "src/App.generated.jsx",
+            "src/Keep.generated.jsx",
+            "src/dist/App.jsx",
+            "src/lib/dist/App.jsx",
+        ] {
+            let file = folder.join(file);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, "<div />").unwrap();
+        }
+        fs::write(
+            folder.join(GITIGNORE),
+            "# Outputs\n/build/\n*.generated.jsx\n",
+        )
+        .unwrap();
+        fs::write(
+            folder.join("src").join(GITIGNORE),
+            "!Keep.generated.jsx\n/dist\n",
+        )
+        .unwrap();
+
+        let files = source_files(&folder).unwrap();
+        let files: Vec<_> = files
+            .iter()
+            .map(|file| file.strip_prefix(&folder).unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                "App.jsx",
+                "src/App.jsx",
+                "src/Keep.generated.jsx",
+                "src/lib/dist/App.jsx"
+            ]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
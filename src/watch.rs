@@ -0,0 +1,338 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{error::Error, walk};
+
+/// Time between two looks at the watched files, when file system notifications are not
+/// available.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Time given to the other events of a change before looking at the files (editors often save
+/// a file in several steps).
+#[cfg(target_os = "linux")]
+const SETTLE_DELAY: Duration = Duration::from_millis(50);
+
+/// Modification time and size of the source files of a path.
+#[derive(Debug, PartialEq, Default)]
+pub struct Snapshot {
+    files: BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+}
+
+impl Snapshot {
+    pub fn take(path: &Path) -> Result<Self, Error> {
+        let files = walk::source_files(path)?
+            .into_iter()
+            .filter_map(|file| Some((file.clone(), state(&file)?)))
+            .collect();
+
+        Ok(Self { files })
+    }
+
+    /// Files added or modified since a previous snapshot, sorted by path.
+    pub fn changed_since(&self, previous: &Snapshot) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .filter(|(file, state)| previous.files.get(*file) != Some(state))
+            .map(|(file, _)| file.clone())
+            .collect()
+    }
+
+    /// Take the current state of some files, like the files a run rewrote in place.
+    pub fn refresh(&mut self, files: &[PathBuf]) {
+        for file in files {
+            match state(file) {
+                Some(state) => self.files.insert(file.clone(), state),
+                None => self.files.remove(file),
+            };
+        }
+    }
+}
+
+fn state(file: &Path) -> Option<(Option<SystemTime>, u64)> {
+    let metadata = fs::metadata(file).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+/// Files of the path added or modified since the snapshot, which becomes the current state.
+/// Taken before processing the files, so the ones saved during the processing are found by the
+/// next call.
+pub fn poll_once(snapshot: &mut Snapshot, path: &Path) -> Result<Vec<PathBuf>, Error> {
+    let current = Snapshot::take(path)?;
+    let changed = current.changed_since(snapshot);
+    *snapshot = current;
+
+    Ok(changed)
+}
+
+/// Wait for changes of the files of a path: notified by inotify on Linux, polled elsewhere or
+/// when inotify is not available (e.g. too many watches).
+pub struct Watcher {
+    path: PathBuf,
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify::Inotify>,
+}
+
+impl Watcher {
+    /// Start watching the folders of a path. Changes made from now on are notified.
+    pub fn new(path: &Path) -> Self {
+        let mut watcher = Self {
+            path: path.to_path_buf(),
+            #[cfg(target_os = "linux")]
+            inotify: inotify::Inotify::new().ok(),
+        };
+
+        watcher.watch_folders();
+        watcher
+    }
+
+    /// Block until a file may have changed, or the timeout is reached. Returns whether a
+    /// change was notified (always true when polling).
+    pub fn wait(&mut self, timeout: Duration) -> bool {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = &self.inotify {
+            match inotify.wait(timeout) {
+                Ok(true) => {
+                    thread::sleep(SETTLE_DELAY);
+                    if inotify.drain().is_err() {
+                        self.inotify = None;
+                    }
+                    // Folders created by the change are watched before looking at the files
+                    self.watch_folders();
+                    return true;
+                }
+                Ok(false) => return false,
+                Err(_) => self.inotify = None,
+            }
+        }
+
+        thread::sleep(timeout.min(POLL_INTERVAL));
+        true
+    }
+
+    /// Add a watch to every folder of the path (watching a folder twice is a no-op). Falls back
+    /// to polling when one can not be watched.
+    fn watch_folders(&mut self) {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = &self.inotify {
+            let watched = walk::source_folders(&self.path).is_ok_and(|folders| {
+                folders
+                    .iter()
+                    .all(|folder| inotify.add_watch(folder).is_ok() || !folder.exists())
+            });
+            if !watched {
+                self.inotify = None;
+            }
+        }
+    }
+}
+
+/// Minimal binding of the Linux inotify API.
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::{
+        ffi::{c_char, c_int, c_short, c_ulong, CString},
+        fs::File,
+        io::{self, Read},
+        os::{
+            fd::{AsRawFd, FromRawFd},
+            unix::ffi::OsStrExt,
+        },
+        path::Path,
+        time::Duration,
+    };
+
+    const IN_NONBLOCK: c_int = 0o4000;
+    const IN_CLOEXEC: c_int = 0o2000000;
+
+    const IN_MODIFY: u32 = 0x2;
+    const IN_ATTRIB: u32 = 0x4;
+    const IN_CLOSE_WRITE: u32 = 0x8;
+    const IN_MOVED_FROM: u32 = 0x40;
+    const IN_MOVED_TO: u32 = 0x80;
+    const IN_CREATE: u32 = 0x100;
+    const IN_DELETE: u32 = 0x200;
+    /// Events of the entries of a folder that can add or modify a source file.
+    const MASK: u32 = IN_MODIFY
+        | IN_ATTRIB
+        | IN_CLOSE_WRITE
+        | IN_MOVED_FROM
+        | IN_MOVED_TO
+        | IN_CREATE
+        | IN_DELETE;
+
+    const POLLIN: c_short = 0x1;
+
+    #[repr(C)]
+    struct PollFd {
+        fd: c_int,
+        events: c_short,
+        revents: c_short,
+    }
+
+    extern "C" {
+        fn inotify_init1(flags: c_int) -> c_int;
+        fn inotify_add_watch(fd: c_int, pathname: *const c_char, mask: u32) -> c_int;
+        fn poll(fds: *mut PollFd, nfds: c_ulong, timeout: c_int) -> c_int;
+    }
+
+    /// Non-blocking inotify instance, closed on drop.
+    pub struct Inotify {
+        file: File,
+    }
+
+    impl Inotify {
+        pub fn new() -> io::Result<Self> {
+            // SAFETY: no pointer is passed, the returned descriptor is checked before use.
+            let fd = unsafe { inotify_init1(IN_NONBLOCK | IN_CLOEXEC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // SAFETY: the descriptor was just opened and is owned by the file from now on.
+            let file = unsafe { File::from_raw_fd(fd) };
+            Ok(Self { file })
+        }
+
+        pub fn add_watch(&self, folder: &Path) -> io::Result<()> {
+            let path = CString::new(folder.as_os_str().as_bytes())
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+            // SAFETY: the path is a valid C string living for the duration of the call.
+            let wd = unsafe { inotify_add_watch(self.file.as_raw_fd(), path.as_ptr(), MASK) };
+            if wd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// Wait for events until the timeout, returning whether some are pending.
+        pub fn wait(&self, timeout: Duration) -> io::Result<bool> {
+            let mut fds = PollFd {
+                fd: self.file.as_raw_fd(),
+                events: POLLIN,
+                revents: 0,
+            };
+            let timeout = c_int::try_from(timeout.as_millis()).unwrap_or(-1);
+
+            loop {
+                // SAFETY: `fds` is a single valid `pollfd` for the duration of the call.
+                let ready = unsafe { poll(&mut fds, 1, timeout) };
+                if ready >= 0 {
+                    return Ok(ready > 0);
+                }
+
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+        }
+
+        /// Discard the pending events: the changed files are found from the snapshots.
+        pub fn drain(&self) -> io::Result<()> {
+            let mut buffer = [0; 4096];
+
+            loop {
+                match (&self.file).read(&mut buffer) {
+                    Ok(0) => return Ok(()),
+                    Ok(_) => (),
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_added_and_modified_files() {
+        let folder = std::env::temp_dir().join("propsreplacer-watch-test");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("a.jsx"), "<a />").unwrap();
+        fs::write(folder.join("b.jsx"), "<b />").unwrap();
+
+        let before = Snapshot::take(&folder).unwrap();
+        assert!(Snapshot::take(&folder)
+            .unwrap()
+            .changed_since(&before)
+            .is_empty());
+
+        fs::write(folder.join("b.jsx"), "<b id='b' />").unwrap();
+        fs::write(folder.join("c.jsx"), "<c />").unwrap();
+        fs::write(folder.join("notes.txt"), "").unwrap();
+
+        let changed = Snapshot::take(&folder).unwrap().changed_since(&before);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(changed, vec![folder.join("b.jsx"), folder.join("c.jsx")]);
+    }
+
+    #[test]
+    fn poll_files_changed_during_a_run() {
+        let folder = std::env::temp_dir().join("propsreplacer-watch-poll");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("a.jsx"), "<a />").unwrap();
+        fs::write(folder.join("b.jsx"), "<b />").unwrap();
+
+        let mut snapshot = Snapshot::default();
+        assert_eq!(
+            poll_once(&mut snapshot, &folder).unwrap(),
+            vec![folder.join("a.jsx"), folder.join("b.jsx")]
+        );
+
+        // Saved while the files of the first poll are processed
+        fs::write(folder.join("b.jsx"), "<b id='b' />").unwrap();
+        assert_eq!(
+            poll_once(&mut snapshot, &folder).unwrap(),
+            vec![folder.join("b.jsx")]
+        );
+        assert!(poll_once(&mut snapshot, &folder).unwrap().is_empty());
+
+        // New file
+        fs::create_dir_all(folder.join("lib")).unwrap();
+        fs::write(folder.join("lib/c.jsx"), "<c />").unwrap();
+        assert_eq!(
+            poll_once(&mut snapshot, &folder).unwrap(),
+            vec![folder.join("lib/c.jsx")]
+        );
+
+        // Rewritten in place by a run
+        fs::write(folder.join("a.jsx"), "<a  />").unwrap();
+        snapshot.refresh(&[folder.join("a.jsx")]);
+        assert!(poll_once(&mut snapshot, &folder).unwrap().is_empty());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn notify_changes_in_new_folders() {
+        let folder = std::env::temp_dir().join("propsreplacer-watch-notify");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+
+        let mut watcher = Watcher::new(&folder);
+        assert!(!watcher.wait(Duration::from_millis(10)));
+
+        fs::create_dir_all(folder.join("lib")).unwrap();
+        assert!(watcher.wait(Duration::from_secs(5)));
+        assert!(!watcher.wait(Duration::from_millis(10)));
+
+        fs::write(folder.join("lib/a.jsx"), "<a />").unwrap();
+        assert!(watcher.wait(Duration::from_secs(5)));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs;
+
+use react_props_replacer::{
+    parse, transform, Action, Component, ComponentType, Edits, ElementTransform, Error, PropValue,
+};
+
+mod integration;
+use integration::files::*;
+
+mod api {
+    use super::*;
+
+    #[test]
+    fn parse_file_components_and_props() {
+        let content = fs::read_to_string(FILE_HTML_DATATESTID).unwrap();
+        let document = parse(&content);
+
+        let button = document
+            .components()
+            .iter()
+            .find(|component| component.name == "button")
+            .unwrap();
+        assert_eq!(button.typo, ComponentType::HtmlElement);
+        assert_eq!(
+            button
+                .props()
+                .iter()
+                .map(|prop| prop.name)
+                .collect::<Vec<_>>(),
+            vec![
+                "className",
+                "type",
+                "data-testid",
+                "data-cy",
+                "onClick",
+                "aria-expanded"
+            ]
+        );
+
+        let data_cy = button.prop("data-cy").unwrap();
+        assert_eq!(data_cy.value, PropValue::Literal("\"cy_test_id\""));
+        assert_eq!(
+            &content[data_cy.span.start..data_cy.span.end],
+            "data-cy=\"cy_test_id\""
+        );
+        assert_eq!(document.location(data_cy.span.start).line, 12);
+
+        let last = document.components().last().unwrap();
+        assert_eq!(last.name, "MyLastComponent");
+        assert_eq!(last.typo, ComponentType::ReactComponent);
+    }
+
+    #[test]
+    fn transform_file_content() {
+        let content = fs::read_to_string(FILE_HTML_DATATESTID).unwrap();
+        let expected = fs::read_to_string(FILE_HTML_DATATESTID_ORIGINAL_UPDATED).unwrap();
+
+        let result = transform(&content, &[Action::RemoveProp("data-testid".to_string())]);
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[test]
+    fn transform_with_conflicting_actions() {
+        let result = transform(
+            "<div id='a' />",
+            &[
+                Action::RemoveProp("id".to_string()),
+                Action::RenameProp("id".to_string(), "key".to_string()),
+            ],
+        );
+        assert!(matches!(result, Err(Error::ActionConflict(_))));
+    }
+
+    /// Links opening a new tab get `rel="noopener"`, replacing any other `rel` value.
+    struct NoOpener;
+
+    impl ElementTransform for NoOpener {
+        fn name(&self) -> &str {
+            "no-opener"
+        }
+
+        fn visit(&self, component: &Component, edits: &mut Edits) -> Result<(), String> {
+            if component.name != "a" || component.prop("target").is_none() {
+                return Ok(());
+            }
+            match component.prop("rel") {
+                Some(prop) => edits.set_prop_value(prop, "\"noopener\""),
+                None => edits.add_prop(component, "rel", Some("\"noopener\"")),
+            };
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn transform_with_custom_action() {
+        let result = transform(
+            "<div>\n  <a target='_blank' rel='opener' id='a'>A</a>\n  <a target='_blank' {...{ rel: 'x' }}>B</a>\n  <a href='/' id='c'>C</a>\n  <Link target='_blank' />\n</div>",
+            &[
+                Action::RemoveProp("id".to_string()),
+                Action::custom(NoOpener),
+            ],
+        );
+        assert_eq!(
+            result.unwrap(),
+            "<div>\n  <a target='_blank' rel=\"noopener\" >A</a>\n  <a target='_blank' {...{ rel: 'x' }}>B</a>\n  <a href='/' >C</a>\n  <Link target='_blank' />\n</div>"
+        );
+    }
+}
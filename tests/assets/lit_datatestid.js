@@ -0,0 +1,14 @@
+import { LitElement, html } from 'lit';
+
+export class SignIn extends LitElement {
+  render() {
+    return html`
+      <form data-testid=form @submit=${this.submit}>
+        <input type=email .value=${this.email} ?disabled=${this.busy > 0} data-testid=${this.testId}>
+        <ul>
+          ${this.errors.map((error) => html`<li data-testid="error">${error}</li>`)}
+        </ul>
+      </form>
+    `;
+  }
+}
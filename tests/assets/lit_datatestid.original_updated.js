@@ -0,0 +1,14 @@
+import { LitElement, html } from 'lit';
+
+export class SignIn extends LitElement {
+  render() {
+    return html`
+      <form  @submit=${this.submit}>
+        <input type=email .value=${this.email} ?disabled=${this.busy > 0} >
+        <ul>
+          ${this.errors.map((error) => html`<li >${error}</li>`)}
+        </ul>
+      </form>
+    `;
+  }
+}
@@ -0,0 +1,11 @@
+import Button from './components/Button';
+
+const Form = ({ onSubmit }) => (
+  <form className="form" onSubmit={onSubmit}>
+    <label for="name" className="label">Name</label>
+    <input id="name" type="text" required />
+    <Button type="submit" data-testid="send">Send</Button>
+  </form>
+);
+
+export default Form;
@@ -0,0 +1,7 @@
+const Button = ({ children, ...props }: Props) => (
+  <button type="button" className="button" {...props}>
+    {children}
+  </button>
+);
+
+export default Button;
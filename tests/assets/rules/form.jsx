@@ -0,0 +1,8 @@
+const Form = () => (
+  <form data-testid="form">
+    <label for="name" data-cy="label">Name</label>
+    <input id="name" data-testid="input" />
+  </form>
+);
+
+export default Form;
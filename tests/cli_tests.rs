@@ -0,0 +1,144 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+mod integration;
+use integration::files::*;
+
+mod cli {
+    use super::*;
+
+    fn run_cli(args: &[&str], stdin: &[u8]) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_react-props-replacer"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Binary can not be executed");
+
+        child.stdin.take().unwrap().write_all(stdin).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn run_as_filter_from_stdin_to_stdout() {
+        let output = run_cli(
+            &["-", "--remove-prop", "--no-config"],
+            b"<div data-testid=\"id\" className=\"box\">\r\n</div>",
+        );
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"<div  className=\"box\">\r\n</div>");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("SUMMARY"));
+    }
+
+    #[test]
+    fn run_with_input_file_to_stdout() {
+        let output = run_cli(&[FILE_HTML_DATATESTID, "-o", "-", "--remove-prop"], b"");
+
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            std::fs::read_to_string(FILE_HTML_DATATESTID_ORIGINAL_UPDATED).unwrap()
+        );
+    }
+
+    #[test]
+    fn run_with_idempotency_check() {
+        let args = [
+            "rename",
+            "-",
+            "--rename-prop",
+            "for=htmlFor,htmlFor=for",
+            "--idempotent",
+            "--no-config",
+        ];
+        let output = run_cli(&args, b"<label for=\"a\" htmlFor=\"b\">A</label>");
+
+        assert_eq!(output.status.code(), Some(6));
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("-:1:8: output refused, actions not idempotent"));
+
+        let args = [args[0], args[1], args[2], "for=htmlFor", args[4], args[5]];
+        let output = run_cli(&args, b"<label for=\"a\">A</label>");
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"<label htmlFor=\"a\">A</label>");
+    }
+
+    #[test]
+    fn run_with_json_summary() {
+        let output = run_cli(
+            &["-", "--remove-prop", "--no-config", "--report", "json"],
+            b"<div>\n  <p data-testid=\"p\" />\n</div>",
+        );
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"<div>\n  <p  />\n</div>");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.trim_start().starts_with('{'));
+        assert!(stderr.contains("\"action\": \"removed\",\n"));
+        assert!(stderr.contains("\"old_value\": \"\\\"p\\\"\",\n"));
+        assert!(stderr.contains("\"line\": 2,\n"));
+        assert!(!stderr.contains("Process completed"));
+    }
+
+    #[test]
+    fn check_with_sarif_and_junit_results() {
+        let args = ["check", "-", "--remove-prop", "--no-config", "--report"];
+        let input = b"<div>\n  <p data-testid=\"p\" />\n</div>";
+
+        let output = run_cli(&[&args[..], &["sarif"]].concat(), input);
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\"ruleId\": \"remove-prop/data-testid\",\n"));
+        assert!(stdout.contains("\"startLine\": 2,\n"));
+
+        let output = run_cli(&[&args[..], &["junit"]].concat(), input);
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("<testsuites name=\"react-props-replacer\" tests=\"1\" failures=\"1\"")
+        );
+        assert!(stdout.contains("-:2:6: [remove-prop/data-testid]"));
+    }
+
+    #[test]
+    fn report_folder_as_csv() {
+        let output = run_cli(&["report", SOURCES_FOLDER, "--format", "csv"], b"");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("tag,prop,uses,distinct_values,examples\n"));
+        assert!(stdout.contains("\nlabel,for,1,1,./tests/assets/report/Form.jsx:5\n"));
+        assert!(stdout.contains("/components/Button.tsx:2\n"));
+        assert!(!stdout.contains("node_modules"));
+    }
+
+    #[cfg(feature = "scripting")]
+    #[test]
+    fn run_with_failing_script_rule() {
+        let output = run_cli(
+            &["-", "--config", FILE_RULES_SCRIPT_ERROR_CONFIG],
+            b"<div>\n  <img alt=\"\" />\n  <img src=\"a.png\" />\n</div>",
+        );
+
+        assert_eq!(output.status.code(), Some(7));
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("-:3:3: `"));
+        assert!(stderr.contains("script_error.rhai` failed on <img>: "));
+        assert!(stderr.contains("missing alt text"));
+    }
+
+    #[test]
+    fn run_with_unknown_option() {
+        let output = run_cli(&[FILE_HTML_DATATESTID, "--unknown"], b"");
+
+        assert_eq!(output.status.code(), Some(2));
+        assert!(output.stdout.is_empty());
+    }
+}
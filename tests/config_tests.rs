@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use react_props_replacer::{Action, ConfigBuilder};
+use react_props_replacer::{Action, ConfigBuilder, Error};
 
 mod integration;
 use integration::files::*;
@@ -39,10 +39,113 @@ mod config {
         );
     }
 
+    #[test]
+    fn create_config_from_args_with_separate_optional_values() {
+        let config = ConfigBuilder::new()
+            .parse_args(&[FILE_HTML_DATATESTID, "--remove-prop", "data-cy"].map(|v| v.to_string()))
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.actions,
+            vec![Action::RemoveProp("data-cy".to_string())]
+        );
+
+        let config = ConfigBuilder::new()
+            .parse_args(
+                &[
+                    FILE_HTML_DATATESTID,
+                    "--output",
+                    "out.jsx",
+                    "--remove-prop",
+                    "data-cy",
+                ]
+                .map(|v| v.to_string()),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(config.output, PathBuf::from("out.jsx"));
+        assert_eq!(
+            config.actions,
+            vec![Action::RemoveProp("data-cy".to_string())]
+        );
+
+        // Before the input, an existing path is the input
+        let config = ConfigBuilder::new()
+            .parse_args(&["--remove-prop", FILE_HTML_DATATESTID].map(|v| v.to_string()))
+            .build()
+            .unwrap();
+        assert_eq!(config.input, PathBuf::from(FILE_HTML_DATATESTID));
+        assert_eq!(
+            config.actions,
+            vec![Action::RemoveProp("data-testid".to_string())]
+        );
+    }
+
     #[test]
     fn create_config_from_empty_args() {
         let result = ConfigBuilder::new().parse_args(&[]).build();
-        assert!(result.is_err());
+        assert!(matches!(result, Err(Error::Config(_))));
+        assert_eq!(result.unwrap_err().exit_code(), 2);
+    }
+
+    #[test]
+    fn create_config_from_args_with_repeated_flags() {
+        let config = ConfigBuilder::new()
+            .parse_args(
+                &[
+                    "rename",
+                    FILE_HTML_DATATESTID,
+                    "--rename-prop",
+                    "class=className,for=htmlFor",
+                    "--output=./tests/assets/html--datatestid.jsx",
+                ]
+                .map(|v| v.to_string()),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(config.actions.len(), 2);
+        assert_eq!(
+            config.output,
+            PathBuf::from("./tests/assets/html--datatestid.jsx")
+        );
+    }
+
+    #[test]
+    fn create_config_from_args_with_unknown_option() {
+        let result = ConfigBuilder::new()
+            .parse_args(
+                &[FILE_HTML_DATATESTID, "--remove-prop", "--unknown"].map(|v| v.to_string()),
+            )
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn create_config_with_conflicting_actions() {
+        let result = ConfigBuilder::new()
+            .parse_args(
+                &[
+                    FILE_HTML_DATATESTID,
+                    "--remove-prop",
+                    "--rename-prop",
+                    "data-testid=data-test",
+                ]
+                .map(|v| v.to_string()),
+            )
+            .build();
+        assert!(matches!(result, Err(Error::ActionConflict(_))));
+        assert_eq!(result.unwrap_err().exit_code(), 5);
+    }
+
+    #[test]
+    fn create_config_with_duplicated_actions() {
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_HTML_DATATESTID))
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .build()
+            .unwrap();
+        assert_eq!(config.actions.len(), 1);
     }
 
     #[test]
@@ -76,4 +179,86 @@ mod config {
             .build();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn create_config_with_discovered_config_file() {
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_RULES_FORM))
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.actions,
+            vec![Action::OnElement(
+                "label".to_string(),
+                Box::new(Action::RenameProp("for".to_string(), "htmlFor".to_string()))
+            )]
+        );
+
+        let config = ConfigBuilder::new()
+            .parse_args(&[FILE_RULES_FORM, "--env", "production"].map(|v| v.to_string()))
+            .build()
+            .unwrap();
+        assert_eq!(config.actions.len(), 3);
+        assert_eq!(
+            config.actions.first().unwrap(),
+            &Action::RemoveProp("data-testid".to_string())
+        );
+    }
+
+    #[test]
+    fn create_config_without_config_file() {
+        let result = ConfigBuilder::new()
+            .parse_args(&[FILE_RULES_FORM, "--no-config"].map(|v| v.to_string()))
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_HTML_DATATESTID))
+            .config_file(PathBuf::from(FILE_RULES_CONFIG))
+            .env("production".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(config.actions.len(), 3);
+    }
+
+    #[test]
+    fn create_config_with_changed_since_a_git_ref() {
+        let result = ConfigBuilder::new()
+            .parse_args(
+                &[
+                    FILE_HTML_DATATESTID,
+                    "--remove-prop",
+                    "--changed-since",
+                    "main",
+                ]
+                .map(|v| v.to_string()),
+            )
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let config = ConfigBuilder::new()
+            .parse_args(
+                &[
+                    "check",
+                    SOURCES_FOLDER,
+                    "--remove-prop",
+                    "--changed-since",
+                    "main",
+                ]
+                .map(|v| v.to_string()),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(config.changed_since.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn create_config_from_stdin() {
+        let config = ConfigBuilder::new()
+            .parse_args(&["-", "--remove-prop", "--no-config"].map(|v| v.to_string()))
+            .build()
+            .unwrap();
+        assert!(config.reads_stdin());
+        assert!(config.writes_stdout());
+    }
 }
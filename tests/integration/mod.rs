@@ -4,12 +4,59 @@ pub mod files {
     pub const FILE_HTML_DATATESTID_UPDATED: &str = "./tests/assets/html_datatestid.updated.jsx";
     pub const FILE_HTML_DATATESTID_ORIGINAL_UPDATED: &str =
         "./tests/assets/html_datatestid.original_updated.jsx";
+    pub const FILE_HTML_DATATESTID_SPREAD: &str = "./tests/assets/html_datatestid_spread.jsx";
+    pub const FILE_HTML_DATATESTID_SPREAD_UPDATED: &str =
+        "./tests/assets/html_datatestid_spread.updated.jsx";
+    pub const FILE_HTML_DATATESTID_SPREAD_PRESET_UPDATED: &str =
+        "./tests/assets/html_datatestid_spread.preset_updated.jsx";
+    pub const FILE_HTML_DATATESTID_VERIFIED: &str = "./tests/assets/html_datatestid.verified.jsx";
     pub const FILE_REACT_DATATESTID: &str = "./tests/assets/react_datatestid.jsx";
     pub const FILE_REACT_DATATESTID_UPDATED: &str = "./tests/assets/react_datatestid.updated.jsx";
     pub const FILE_REACT_DATATESTID_ORIGINAL_UPDATED: &str =
         "./tests/assets/react_datatestid.original_updated.jsx";
 
+    pub const FILE_VUE_DATATESTID: &str = "./tests/assets/vue_datatestid.vue";
+    pub const FILE_VUE_DATATESTID_UPDATED: &str = "./tests/assets/vue_datatestid.updated.vue";
+    pub const FILE_VUE_DATATESTID_ORIGINAL_UPDATED: &str =
+        "./tests/assets/vue_datatestid.original_updated.vue";
+
+    pub const FILE_SVELTE_DATATESTID: &str = "./tests/assets/svelte_datatestid.svelte";
+    pub const FILE_SVELTE_DATATESTID_UPDATED: &str =
+        "./tests/assets/svelte_datatestid.updated.svelte";
+    pub const FILE_SVELTE_DATATESTID_ORIGINAL_UPDATED: &str =
+        "./tests/assets/svelte_datatestid.original_updated.svelte";
+    pub const FILE_ASTRO_DATATESTID: &str = "./tests/assets/astro_datatestid.astro";
+    pub const FILE_ASTRO_DATATESTID_UPDATED: &str = "./tests/assets/astro_datatestid.updated.astro";
+    pub const FILE_ASTRO_DATATESTID_ORIGINAL_UPDATED: &str =
+        "./tests/assets/astro_datatestid.original_updated.astro";
+
+    pub const FILE_PLAIN_HTML_DATATESTID: &str = "./tests/assets/html_datatestid.html";
+    pub const FILE_PLAIN_HTML_DATATESTID_UPDATED: &str =
+        "./tests/assets/html_datatestid.updated.html";
+    pub const FILE_PLAIN_HTML_DATATESTID_ORIGINAL_UPDATED: &str =
+        "./tests/assets/html_datatestid.original_updated.html";
+    pub const FILE_LIT_DATATESTID: &str = "./tests/assets/lit_datatestid.js";
+    pub const FILE_LIT_DATATESTID_UPDATED: &str = "./tests/assets/lit_datatestid.updated.js";
+    pub const FILE_LIT_DATATESTID_ORIGINAL_UPDATED: &str =
+        "./tests/assets/lit_datatestid.original_updated.js";
+
+    pub const FILE_HTML_DATATESTID_CRLF: &str = "./tests/assets/html_datatestid_crlf.jsx";
+    pub const FILE_HTML_DATATESTID_CRLF_UPDATED: &str =
+        "./tests/assets/html_datatestid_crlf.updated.jsx";
+    pub const FILE_HTML_DATATESTID_CRLF_ORIGINAL_UPDATED: &str =
+        "./tests/assets/html_datatestid_crlf.original_updated.jsx";
+    pub const FILE_NON_UTF8: &str = "./tests/assets/non_utf8.jsx";
+    pub const FILE_NON_UTF8_UPDATED: &str = "./tests/assets/non_utf8.updated.jsx";
+
+    pub const FILE_RULES_FORM: &str = "./tests/assets/rules/form.jsx";
+    pub const FILE_RULES_CONFIG: &str = "./tests/assets/rules/propsreplacer.json";
+    pub const FILE_RULES_SCRIPT_ERROR_CONFIG: &str = "./tests/assets/rules/script_error.json";
+
+    pub const SOURCES_FOLDER: &str = "./tests/assets/report";
+    pub const SOURCES_FOLDER_UPDATED: &str = "./tests/assets/report.updated";
+
     pub const FILE_NOT_EXISTS: &str = "./tests/assets/file_not_exists.jsx";
+    pub const FILE_IN_MISSING_FOLDER: &str = "./tests/assets/missing_folder/file.jsx";
 
     pub const ASSETS_FOLDER: &str = "./tests/assets";
 }
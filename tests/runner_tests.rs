@@ -3,7 +3,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use react_props_replacer::{run, Action, Config, ConfigBuilder};
+use react_props_replacer::{
+    run, Action, Component, Config, ConfigBuilder, Edits, ElementTransform, Error, Mode, Preset,
+};
 
 mod integration;
 use integration::files::*;
@@ -53,4 +55,467 @@ mod runner {
 
         prepare_file(FILE_REACT_DATATESTID_UPDATED);
     }
+
+    #[test]
+    fn run_config_with_vue_file() {
+        prepare_file(FILE_VUE_DATATESTID_UPDATED);
+
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_VUE_DATATESTID))
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .verify(true)
+            .build()
+            .unwrap();
+        assert!(run(&config).is_ok());
+        assert_eq!(
+            fs::read_to_string(FILE_VUE_DATATESTID_UPDATED).unwrap(),
+            fs::read_to_string(FILE_VUE_DATATESTID_ORIGINAL_UPDATED).unwrap()
+        );
+
+        prepare_file(FILE_VUE_DATATESTID_UPDATED);
+    }
+
+    #[test]
+    fn run_config_with_svelte_and_astro_files() {
+        for (input, output, expected) in [
+            (
+                FILE_SVELTE_DATATESTID,
+                FILE_SVELTE_DATATESTID_UPDATED,
+                FILE_SVELTE_DATATESTID_ORIGINAL_UPDATED,
+            ),
+            (
+                FILE_ASTRO_DATATESTID,
+                FILE_ASTRO_DATATESTID_UPDATED,
+                FILE_ASTRO_DATATESTID_ORIGINAL_UPDATED,
+            ),
+        ] {
+            prepare_file(output);
+
+            let config = ConfigBuilder::new()
+                .input(PathBuf::from(input))
+                .add_action(Action::RemoveProp("data-testid".to_string()))
+                .verify(true)
+                .build()
+                .unwrap();
+            assert!(run(&config).is_ok());
+            assert_eq!(
+                fs::read_to_string(output).unwrap(),
+                fs::read_to_string(expected).unwrap()
+            );
+
+            prepare_file(output);
+        }
+    }
+
+    #[test]
+    fn run_config_with_html_files_and_tagged_templates() {
+        for (input, output, expected) in [
+            (
+                FILE_PLAIN_HTML_DATATESTID,
+                FILE_PLAIN_HTML_DATATESTID_UPDATED,
+                FILE_PLAIN_HTML_DATATESTID_ORIGINAL_UPDATED,
+            ),
+            (
+                FILE_LIT_DATATESTID,
+                FILE_LIT_DATATESTID_UPDATED,
+                FILE_LIT_DATATESTID_ORIGINAL_UPDATED,
+            ),
+        ] {
+            prepare_file(output);
+
+            let config = ConfigBuilder::new()
+                .input(PathBuf::from(input))
+                .add_action(Action::RemoveProp("data-testid".to_string()))
+                .verify(true)
+                .build()
+                .unwrap();
+            assert!(run(&config).is_ok());
+            assert_eq!(
+                fs::read_to_string(output).unwrap(),
+                fs::read_to_string(expected).unwrap()
+            );
+
+            prepare_file(output);
+        }
+    }
+
+    #[test]
+    fn run_config_with_preset() {
+        prepare_file(FILE_HTML_DATATESTID_SPREAD_UPDATED);
+
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_HTML_DATATESTID_SPREAD))
+            .preset(Preset::StripTestIds)
+            .build()
+            .expect("Config error");
+        let result = run(&config);
+        assert!(result.is_ok(), "runner process throws an error");
+
+        let result_file_str = fs::read_to_string(FILE_HTML_DATATESTID_SPREAD_UPDATED).unwrap();
+        let expected_file_str =
+            fs::read_to_string(FILE_HTML_DATATESTID_SPREAD_PRESET_UPDATED).unwrap();
+        assert_eq!(result_file_str, expected_file_str);
+
+        prepare_file(FILE_HTML_DATATESTID_SPREAD_UPDATED);
+    }
+
+    #[test]
+    fn run_config_keeps_bom_and_crlf_line_endings() {
+        prepare_file(FILE_HTML_DATATESTID_CRLF_UPDATED);
+
+        let config = buil_config(FILE_HTML_DATATESTID_CRLF);
+        let result = run(&config);
+        assert!(result.is_ok(), "runner process throws an error");
+
+        let result_file = fs::read(FILE_HTML_DATATESTID_CRLF_UPDATED).unwrap();
+        let expected_file = fs::read(FILE_HTML_DATATESTID_CRLF_ORIGINAL_UPDATED).unwrap();
+        assert_eq!(result_file, expected_file);
+
+        prepare_file(FILE_HTML_DATATESTID_CRLF_UPDATED);
+    }
+
+    #[test]
+    fn run_config_with_non_utf8_file() {
+        prepare_file(FILE_NON_UTF8_UPDATED);
+
+        let config = buil_config(FILE_NON_UTF8);
+        let result = run(&config);
+        assert!(matches!(result, Err(Error::Parse { line: 1, .. })));
+        assert!(result.unwrap_err().to_string().contains(FILE_NON_UTF8));
+        assert!(!Path::new(FILE_NON_UTF8_UPDATED).exists());
+    }
+
+    #[test]
+    fn run_config_with_unwritable_output() {
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_HTML_DATATESTID))
+            .output(PathBuf::from(FILE_IN_MISSING_FOLDER))
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .build()
+            .expect("Config error");
+        let result = run(&config);
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, Error::Io { .. }));
+        assert_eq!(err.exit_code(), 3);
+    }
+
+    #[test]
+    fn run_config_with_folder_in_parallel() {
+        let run_folder = |jobs: usize| {
+            let config = ConfigBuilder::new()
+                .input(PathBuf::from(SOURCES_FOLDER))
+                .add_action(Action::RemoveProp("className".to_string()))
+                .add_action(Action::RenameProp("for".to_string(), "htmlFor".to_string()))
+                .discover_config(false)
+                .jobs(jobs)
+                .build()
+                .expect("Config error");
+            run(&config).expect("runner process throws an error");
+
+            let updated = Path::new(SOURCES_FOLDER_UPDATED);
+            let files = (
+                fs::read_to_string(updated.join("Form.jsx")).unwrap(),
+                fs::read_to_string(updated.join("components/Button.tsx")).unwrap(),
+            );
+            assert!(!updated.join("node_modules").exists());
+            fs::remove_dir_all(updated).unwrap();
+            files
+        };
+
+        let (form, button) = run_folder(4);
+        assert!(form.contains("<label htmlFor=\"name\" >Name</label>"));
+        assert!(button.contains("<button type=\"button\"  {...props}>"));
+        assert_eq!(run_folder(1), (form, button));
+    }
+
+    #[test]
+    fn run_config_with_cache_skips_unchanged_files() {
+        let folder = std::env::temp_dir().join("propsreplacer-cache-run");
+        let (input, output) = (folder.join("src"), folder.join("dist"));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&input).unwrap();
+        fs::copy(FILE_HTML_DATATESTID, input.join("Button.jsx")).unwrap();
+
+        let run_cached = || {
+            let config = ConfigBuilder::new()
+                .input(input.clone())
+                .output(output.clone())
+                .add_action(Action::RemoveProp("data-testid".to_string()))
+                .cache(true)
+                .build()
+                .expect("Config error");
+            run(&config).expect("runner process throws an error");
+            fs::read_to_string(output.join("Button.jsx")).unwrap()
+        };
+
+        let expected = fs::read_to_string(FILE_HTML_DATATESTID_ORIGINAL_UPDATED).unwrap();
+        assert_eq!(run_cached(), expected);
+        assert!(input.join(".propsreplacer-cache").exists());
+
+        // Unchanged input: the output is not written again
+        fs::write(output.join("Button.jsx"), "stale").unwrap();
+        assert_eq!(run_cached(), "stale");
+
+        // Changed input: the file is processed again
+        fs::write(input.join("Button.jsx"), "<p data-testid=\"p\" />").unwrap();
+        assert_eq!(run_cached(), "<p  />");
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn run_config_with_cache_in_check_then_transform_mode() {
+        let folder = std::env::temp_dir().join("propsreplacer-cache-modes");
+        let (input, output) = (folder.join("src"), folder.join("dist"));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("A.jsx"), "<p data-testid=\"a\" />").unwrap();
+
+        let run_cached = |mode: Mode| {
+            let config = ConfigBuilder::new()
+                .input(input.clone())
+                .output(output.clone())
+                .add_action(Action::RemoveProp("data-testid".to_string()))
+                .mode(mode)
+                .cache(true)
+                .build()
+                .expect("Config error");
+            run(&config)
+        };
+
+        assert!(run_cached(Mode::Transform).is_ok());
+        assert_eq!(fs::read_to_string(output.join("A.jsx")).unwrap(), "<p  />");
+
+        // The entry of the check run does not tell the output is up to date
+        fs::write(input.join("A.jsx"), "<p data-testid=\"b\" id=\"b\" />").unwrap();
+        assert!(matches!(
+            run_cached(Mode::Check),
+            Err(Error::CheckFailed(_))
+        ));
+        assert!(run_cached(Mode::Transform).is_ok());
+        assert_eq!(
+            fs::read_to_string(output.join("A.jsx")).unwrap(),
+            "<p  id=\"b\" />"
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn run_config_with_cache_then_idempotent_check() {
+        let folder = std::env::temp_dir().join("propsreplacer-cache-idempotent");
+        let (input, output) = (folder.join("src"), folder.join("dist"));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("A.jsx"), "<label for=\"a\" />").unwrap();
+
+        let run_cached = |idempotent: bool| {
+            let config = ConfigBuilder::new()
+                .input(input.clone())
+                .output(output.clone())
+                .add_action(Action::RenameProp("for".to_string(), "htmlFor".to_string()))
+                .add_action(Action::RenameProp("htmlFor".to_string(), "for".to_string()))
+                .idempotent(idempotent)
+                .cache(true)
+                .build()
+                .expect("Config error");
+            run(&config)
+        };
+
+        assert!(run_cached(false).is_ok());
+        // The unchanged file is checked, not skipped
+        assert!(matches!(run_cached(true), Err(Error::VerifyFailed { .. })));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    /// Rename `data-testid`, with the new name as fingerprint.
+    struct RenameTestId(&'static str);
+
+    impl ElementTransform for RenameTestId {
+        fn name(&self) -> &str {
+            "rename-test-id"
+        }
+
+        fn visit(&self, component: &Component, edits: &mut Edits) -> Result<(), String> {
+            if let Some(prop) = component.prop("data-testid") {
+                edits.rename_prop(prop, self.0);
+            }
+            Ok(())
+        }
+
+        fn fingerprint(&self) -> u64 {
+            self.0
+                .bytes()
+                .fold(0, |hash, c| hash.wrapping_mul(31) + u64::from(c))
+        }
+    }
+
+    #[test]
+    fn run_config_with_cache_and_changed_custom_transform() {
+        let folder = std::env::temp_dir().join("propsreplacer-cache-custom");
+        let (input, output) = (folder.join("src"), folder.join("dist"));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Button.jsx"), "<p data-testid=\"p\" />").unwrap();
+
+        let run_cached = |transform: RenameTestId| {
+            let config = ConfigBuilder::new()
+                .input(input.clone())
+                .output(output.clone())
+                .add_action(Action::custom(transform))
+                .cache(true)
+                .build()
+                .expect("Config error");
+            run(&config).expect("runner process throws an error");
+            fs::read_to_string(output.join("Button.jsx")).unwrap()
+        };
+
+        assert_eq!(run_cached(RenameTestId("data-qa")), "<p data-qa=\"p\" />");
+
+        // Same transformation: the file is skipped
+        fs::write(output.join("Button.jsx"), "stale").unwrap();
+        assert_eq!(run_cached(RenameTestId("data-qa")), "stale");
+
+        // Changed transformation: the file is processed again
+        assert_eq!(run_cached(RenameTestId("data-cy")), "<p data-cy=\"p\" />");
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn run_config_with_verified_output() {
+        prepare_file(FILE_HTML_DATATESTID_VERIFIED);
+
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_HTML_DATATESTID))
+            .output(PathBuf::from(FILE_HTML_DATATESTID_VERIFIED))
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .verify(true)
+            .build()
+            .unwrap();
+        assert!(run(&config).is_ok());
+        assert_eq!(
+            fs::read_to_string(FILE_HTML_DATATESTID_VERIFIED).unwrap(),
+            fs::read_to_string(FILE_HTML_DATATESTID_ORIGINAL_UPDATED).unwrap()
+        );
+        prepare_file(FILE_HTML_DATATESTID_VERIFIED);
+
+        // A value closing the opening tag adds an element
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_HTML_DATATESTID))
+            .output(PathBuf::from(FILE_HTML_DATATESTID_VERIFIED))
+            .add_action(Action::AddProp("title".to_string(), "\"a\"><b".to_string()))
+            .verify(true)
+            .build()
+            .unwrap();
+        let result = run(&config);
+        assert!(matches!(result, Err(Error::VerifyFailed { .. })));
+        assert_eq!(result.unwrap_err().exit_code(), 6);
+        assert!(!Path::new(FILE_HTML_DATATESTID_VERIFIED).exists());
+    }
+
+    #[test]
+    fn run_config_with_source_map() {
+        let folder = std::env::temp_dir().join("propsreplacer-source-map");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        let output = folder.join("Form.jsx");
+
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_HTML_DATATESTID))
+            .output(output.clone())
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .source_map(true)
+            .build()
+            .unwrap();
+        assert!(run(&config).is_ok());
+
+        let updated = fs::read_to_string(FILE_HTML_DATATESTID_ORIGINAL_UPDATED).unwrap();
+        let content = fs::read_to_string(&output).unwrap();
+        assert_eq!(
+            content.strip_suffix("//# sourceMappingURL=Form.jsx.map\n"),
+            Some(updated.as_str())
+        );
+
+        let map = fs::read_to_string(folder.join("Form.jsx.map")).unwrap();
+        assert!(map.contains("\"version\": 3"));
+        assert!(map.contains("\"file\": \"Form.jsx\""));
+        assert!(map.contains("/tests/assets/html_datatestid.jsx\""));
+        assert!(map.contains("\"mappings\": \"AAAA,"));
+
+        // Vue outputs can not link a map, none is written
+        let output = folder.join("Form.vue");
+        let config = ConfigBuilder::new()
+            .input(PathBuf::from(FILE_VUE_DATATESTID))
+            .output(output.clone())
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .source_map(true)
+            .build()
+            .unwrap();
+        assert!(run(&config).is_ok());
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            fs::read_to_string(FILE_VUE_DATATESTID_ORIGINAL_UPDATED).unwrap()
+        );
+        assert!(!folder.join("Form.vue.map").exists());
+
+        // Maps of the stdout or of files rewritten in place are refused
+        let config = ConfigBuilder::new()
+            .input(output.clone())
+            .output(output.clone())
+            .add_action(Action::RemoveProp("data-testid".to_string()))
+            .source_map(true)
+            .build();
+        assert!(matches!(config, Err(Error::Config(_))));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn undo_the_last_run_in_place() {
+        let folder = std::env::temp_dir().join("propsreplacer-undo");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        let file = folder.join("Form.jsx");
+        let original = fs::read_to_string(FILE_HTML_DATATESTID).unwrap();
+        fs::write(&file, &original).unwrap();
+
+        let in_place = || {
+            ConfigBuilder::new()
+                .input(folder.clone())
+                .output(folder.clone())
+                .add_action(Action::RemoveProp("data-testid".to_string()))
+                .build()
+                .unwrap()
+        };
+        let undo = || {
+            ConfigBuilder::new()
+                .parse_args(&["undo".to_string(), folder.display().to_string()])
+                .build()
+                .unwrap()
+        };
+
+        run(&in_place()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            fs::read_to_string(FILE_HTML_DATATESTID_ORIGINAL_UPDATED).unwrap()
+        );
+
+        // A run without edits keeps the journal of the last one
+        run(&in_place()).unwrap();
+        run(&undo()).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+        assert!(matches!(run(&undo()), Err(Error::Config(_))));
+
+        // Files changed since the run are never reverted
+        run(&in_place()).unwrap();
+        let edited = format!("{}\n// edited", fs::read_to_string(&file).unwrap());
+        fs::write(&file, &edited).unwrap();
+        assert!(matches!(run(&undo()), Err(Error::Config(_))));
+        assert_eq!(fs::read_to_string(&file).unwrap(), edited);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
 }